@@ -0,0 +1,56 @@
+//! Generates the registry of solutions that the main binary runs in-process.
+//! With the `in-process` feature, every `src/bin/NN.rs` is included as a module, see `SOLUTIONS` in `src/main.rs`.
+//! Otherwise the registry is empty, so that a solution that does not compile only breaks its own binary.
+use std::{env, fs, path::Path};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    println!("cargo:rerun-if-changed=src/bin");
+
+    let mut days: Vec<u8> = fs::read_dir(&bin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let name = entry.file_name().into_string().ok()?;
+                    let day: u8 = name.strip_suffix(".rs")?.parse().ok()?;
+                    (name.len() == 5 && (1..=25).contains(&day)).then_some(day)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    days.sort_unstable();
+
+    // each solution declares a global allocator when profiling, so they cannot be linked together.
+    if env::var_os("CARGO_FEATURE_IN_PROCESS").is_none()
+        || env::var_os("CARGO_FEATURE_DHAT_HEAP").is_some()
+    {
+        days.clear();
+    }
+
+    let mut registry = String::new();
+
+    for day in &days {
+        let path = bin_dir.join(format!("{day:02}.rs"));
+        registry.push_str(&format!(
+            "#[path = {:?}]\n#[cfg(not(test))]\n#[allow(dead_code, clippy::all, clippy::pedantic)]\nmod day_{day:02};\n",
+            path.display().to_string()
+        ));
+    }
+
+    // the tests of each solution already run as part of its own binary.
+    registry.push_str("\n#[cfg(test)]\npub static SOLUTIONS: &[advent_of_code::template::runner::Solution] = &[];\n");
+    registry.push_str("\n#[cfg(not(test))]\npub static SOLUTIONS: &[advent_of_code::template::runner::Solution] = &[\n");
+    for day in &days {
+        registry.push_str(&format!(
+            "    advent_of_code::template::runner::Solution {{ day: advent_of_code::day!({day}), solve: day_{day:02}::solve_in_process }},\n"
+        ));
+    }
+    registry.push_str("];\n");
+
+    fs::write(Path::new(&out_dir).join("registry.rs"), registry).unwrap();
+}
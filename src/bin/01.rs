@@ -1,18 +1,17 @@
+use advent_of_code::parse::{self, Span};
 use std::collections::HashMap;
 const DELIMITER: &str = "   ";
 advent_of_code::solution!(1);
 
-fn parse_location_line(line: &str) -> (u32, u32) {
-    let parts: Vec<&str> = line.split(DELIMITER).collect();
-    let location1 = parts[0].parse::<u32>().unwrap();
-    let location2 = parts[1].parse::<u32>().unwrap();
-    (location1, location2)
+fn parse_location_line(line: Span) -> parse::Result<(u32, u32)> {
+    let [location1, location2] = line.split_exact(DELIMITER)?;
+    Ok((location1.parse()?, location2.parse()?))
 }
 
 fn load_locations(input: &str) -> (Vec<u32>, Vec<u32>) {
-    let (location_list1, location_list2): (Vec<u32>, Vec<u32>) = input
+    let (location_list1, location_list2): (Vec<u32>, Vec<u32>) = Span::new(input)
         .lines()
-        .map(|line| parse_location_line(&line))
+        .map(|line| parse_location_line(line).unwrap())
         .unzip();
     (location_list1, location_list2)
 }
@@ -48,19 +47,11 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(calculate_similarity_score(&locations.0, &locations.1))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11));
+advent_of_code::examples! {
+    part_one {
+        example => Some(11),
     }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(31));
+    part_two {
+        example => Some(31),
     }
 }
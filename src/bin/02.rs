@@ -1,10 +1,13 @@
+use advent_of_code::parse::Span;
+
 advent_of_code::solution!(2);
 
-fn parse_numbers(input: &str) -> Vec<i32> {
-    input
-        .split_whitespace()
-        .filter_map(|s| s.parse().ok())
-        .collect()
+fn parse_reports(input: &str) -> Vec<Vec<i32>> {
+    Span::new(input)
+        .lines()
+        .map(|line| line.split_whitespace().map(Span::parse).collect())
+        .collect::<Result<_, _>>()
+        .unwrap()
 }
 
 fn is_sorted_sequence(numbers: &[i32]) -> bool {
@@ -16,8 +19,8 @@ fn is_sorted_sequence(numbers: &[i32]) -> bool {
 fn is_near_sorted_sequence(numbers: &[i32]) -> bool {
     is_sorted_sequence(numbers)
         || subsequences_omitting_one(numbers)
-        .iter()
-        .any(|seq| is_sorted_sequence(seq))
+            .iter()
+            .any(|seq| is_sorted_sequence(seq))
 }
 
 fn subsequences_omitting_one(report: &[i32]) -> Vec<Vec<i32>> {
@@ -40,33 +43,27 @@ fn is_window_valid(window: &[i32], increasing: bool) -> bool {
 
 pub fn part_one(input: &str) -> Option<u32> {
     Some(
-        input
-            .lines()
-            .filter(|line| is_sorted_sequence(&parse_numbers(line)))
+        parse_reports(input)
+            .iter()
+            .filter(|report| is_sorted_sequence(report))
             .count() as u32,
     )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     Some(
-        input
-            .lines()
-            .filter(|line| is_near_sorted_sequence(&parse_numbers(line)))
+        parse_reports(input)
+            .iter()
+            .filter(|report| is_near_sorted_sequence(report))
             .count() as u32,
     )
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2));
+advent_of_code::examples! {
+    part_one {
+        example => Some(2),
     }
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(4));
+    part_two {
+        example => Some(4),
     }
-}
\ No newline at end of file
+}
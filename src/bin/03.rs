@@ -32,7 +32,8 @@ fn multiply_capture(x_cap: &str, y_cap: &str) -> Option<u32> {
 pub fn part_one(input: &str) -> Option<u32> {
     let regex = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
     Some(
-        regex.captures_iter(input)
+        regex
+            .captures_iter(input)
             .map(|capture| multiply_capture(&capture[1], &capture[2]).unwrap())
             .sum(),
     )
@@ -44,19 +45,11 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(process_instructions(regex.captures_iter(input)))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(161));
+advent_of_code::examples! {
+    part_one {
+        example => Some(161),
     }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(48));
+    part_two {
+        example => Some(48),
     }
 }
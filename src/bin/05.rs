@@ -1,55 +1,74 @@
+use advent_of_code::graph::Graph;
+use advent_of_code::parse::{self, Span};
 use rayon::prelude::*;
-use std::collections::HashMap;
 
-advent_of_code::solution!(5);
+advent_of_code::solution!(5, parse = parse_input);
 
 #[derive(Clone)]
-struct OrderingRule {
+pub struct OrderingRule {
     left: u32,
     right: u32,
 }
 
-struct Manual {
-    ordering_rules: Vec<OrderingRule>,
+pub struct Manual {
+    ordering_rules: Graph<u32>,
     pages: Vec<Vec<u32>>,
 }
 
 fn parse_input(input: &str) -> Manual {
-    let mut sections = input.split("\n\n");
+    parse_manual(Span::new(input)).unwrap()
+}
 
-    let ordering_rules = parse_ordering_rules(sections.next().unwrap_or(""));
-    let pages = parse_pages(sections.next().unwrap_or(""));
-    Manual {
-        ordering_rules,
-        pages,
-    }
+fn parse_manual(input: Span) -> parse::Result<Manual> {
+    let [rules_section, pages_section] = input.trim().split_exact("\n\n")?;
+
+    Ok(Manual {
+        ordering_rules: parse_ordering_rules(rules_section)?,
+        pages: parse_pages(pages_section)?,
+    })
 }
 
-fn parse_ordering_rules(rules_section: &str) -> Vec<OrderingRule> {
-    fn parse_ordering_rule(rule_str: &str) -> OrderingRule {
-        let rule_parts: Vec<u32> = rule_str.split('|').filter_map(|s| s.parse().ok()).collect();
-        OrderingRule {
-            left: rule_parts[0],
-            right: rule_parts[1],
-        }
+fn parse_ordering_rules(rules_section: Span) -> parse::Result<Graph<u32>> {
+    fn parse_ordering_rule(rule: Span) -> parse::Result<OrderingRule> {
+        let [left, right] = rule.pattern("{}|{}")?;
+        Ok(OrderingRule {
+            left: left.parse()?,
+            right: right.parse()?,
+        })
     }
-    rules_section.par_lines().map(parse_ordering_rule).collect()
+    rules_section
+        .lines()
+        .map(|rule| parse_ordering_rule(rule).map(|rule| (rule.left, rule.right)))
+        .collect()
 }
 
-fn parse_pages(pages_section: &str) -> Vec<Vec<u32>> {
+fn parse_pages(pages_section: Span) -> parse::Result<Vec<Vec<u32>>> {
     pages_section
-        .par_lines()
-        .map(|line| {
-            line.split(',')
-                .filter_map(|s| s.parse::<u32>().ok())
-                .collect()
-        })
+        .lines()
+        .map(|line| line.split(",").map(Span::parse).collect())
         .collect()
 }
 
+/// Whether the page violates none of the ordering rules, i.e. no page number has to come before an earlier one.
+fn is_in_right_order(rules: &Graph<u32>, page: &[u32]) -> bool {
+    page.iter().enumerate().all(|(index, number)| {
+        rules
+            .neighbors(number)
+            .all(|next| !page[..index].contains(next))
+    })
+}
+
+/// Sorts a page according to the ordering rules that apply to it.
+fn sort_page(rules: &Graph<u32>, page: &[u32]) -> Vec<u32> {
+    rules
+        .subgraph(page.iter().copied())
+        .topological_sort()
+        .unwrap_or_else(|cycle| panic!("ordering rules of page {page:?} conflict, {cycle}"))
+}
+
 fn extract_pages<F>(manual: &Manual, condition: F) -> Vec<Vec<u32>>
 where
-    F: Sync + Fn(&[u32], &[OrderingRule]) -> bool,
+    F: Sync + Fn(&[u32], &Graph<u32>) -> bool,
 {
     manual
         .pages
@@ -60,56 +79,18 @@ where
 }
 
 fn extract_pages_in_right_order(manual: &Manual) -> Vec<Vec<u32>> {
-    extract_pages(manual, |page, rules| {
-        filter_rules_for_page(page, rules)
-            .par_iter()
-            .all(|rule| is_in_correct_order(&preprocess_indexes(&page), &rule))
-    })
+    extract_pages(manual, |page, rules| is_in_right_order(rules, page))
 }
 
 fn extract_pages_in_wrong_order(manual: &Manual) -> Vec<Vec<u32>> {
-    extract_pages(manual, |page, rules| {
-        filter_rules_for_page(page, rules)
-            .par_iter()
-            .any(|rule| !is_in_correct_order(&preprocess_indexes(&page), &rule))
-    })
+    extract_pages(manual, |page, rules| !is_in_right_order(rules, page))
 }
 
-fn is_in_correct_order(index_map: &HashMap<u32, usize>, rule: &OrderingRule) -> bool {
-    match (index_map.get(&rule.left), index_map.get(&rule.right)) {
-        (Some(&left_idx), Some(&right_idx)) => left_idx < right_idx,
-        _ => true,
-    }
-}
-
-fn filter_rules_for_page(page: &[u32], rules: &[OrderingRule]) -> Vec<OrderingRule> {
-    rules
-        .iter()
-        .filter(|rule| page.contains(&rule.left) && page.contains(&rule.right))
-        .cloned()
-        .collect()
-}
-
-fn preprocess_indexes(page: &[u32]) -> HashMap<u32, usize> {
-    page.iter().enumerate().map(|(i, &val)| (val, i)).collect()
-}
-
-fn reorder_pages(rules: &[OrderingRule], pages: &mut [Vec<u32>]) {
+fn reorder_pages(rules: &Graph<u32>, pages: &[Vec<u32>]) -> Vec<Vec<u32>> {
     pages
-        .par_iter_mut()
-        .for_each(|page| reorder_page(rules, page));
-}
-
-fn reorder_page(rules: &[OrderingRule], page: &mut Vec<u32>) {
-    page.sort_unstable_by(|&a, &b| {
-        if rules.iter().any(|rule| rule.left == a && rule.right == b) {
-            std::cmp::Ordering::Less
-        } else if rules.iter().any(|rule| rule.left == b && rule.right == a) {
-            std::cmp::Ordering::Greater
-        } else {
-            std::cmp::Ordering::Equal
-        }
-    });
+        .par_iter()
+        .map(|page| sort_page(rules, page))
+        .collect()
 }
 
 fn get_middle_page_sum(pages: &[Vec<u32>]) -> u32 {
@@ -119,32 +100,23 @@ fn get_middle_page_sum(pages: &[Vec<u32>]) -> u32 {
         .sum()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let pages = parse_input(input);
-    let ordered_pages = extract_pages_in_right_order(&pages);
+pub fn part_one(manual: &Manual) -> Option<u32> {
+    let ordered_pages = extract_pages_in_right_order(manual);
     Some(get_middle_page_sum(&ordered_pages))
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let manual = parse_input(input);
-    let mut pages = extract_pages_in_wrong_order(&manual);
-    reorder_pages(&manual.ordering_rules, &mut pages);
-    Some(get_middle_page_sum(&pages))
+pub fn part_two(manual: &Manual) -> Option<u32> {
+    let pages = extract_pages_in_wrong_order(manual);
+    let reordered_pages = reorder_pages(&manual.ordering_rules, &pages);
+    Some(get_middle_page_sum(&reordered_pages))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(143));
+advent_of_code::examples! {
+    parse = parse_input,
+    part_one {
+        example => Some(143),
     }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(123));
+    part_two {
+        example => Some(123),
     }
 }
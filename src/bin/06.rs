@@ -1,176 +1,353 @@
+use advent_of_code::cycle;
+use advent_of_code::geom::{Direction, Point};
+use advent_of_code::grid::Grid;
+#[cfg(feature = "visualize")]
+use advent_of_code::{
+    render,
+    visualize::{self, Color},
+};
 use rayon::prelude::*;
 use std::collections::HashSet;
 
-advent_of_code::solution!(6);
+advent_of_code::solution!(6, parse = parse);
 
-#[derive(Debug, Default, Clone, Copy)]
-struct Guard {
-    x: usize,
-    y: usize,
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Guard {
+    position: Point,
     direction: Direction,
 }
 
 impl Guard {
     fn move_to_next_position(&mut self) {
-        match self.direction {
-            Direction::Up => self.y -= 1,
-            Direction::Down => self.y += 1,
-            Direction::Left => self.x -= 1,
-            Direction::Right => self.x += 1,
-        };
+        self.position = self
+            .position
+            .checked_add_signed(self.direction)
+            .expect("guard left the map");
     }
 
     fn turn_right_90_degrees(&mut self) {
-        self.direction = match self.direction {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
+        self.direction = self.direction.turn_right();
     }
 }
 
 #[derive(Copy, Clone, PartialEq)]
-enum Cell {
+pub enum Cell {
     Obstruction,
     Empty,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
-enum Direction {
-    #[default]
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-fn parse(input: &str) -> (Guard, Vec<Vec<Cell>>) {
-    let mut guard = Guard::default();
-
-    let grid = input
-        .trim_end()
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, char)| match char {
-                    '^' => {
-                        guard.x = x;
-                        guard.y = y;
-                        Cell::Empty
-                    }
-                    '.' => Cell::Empty,
-                    '#' => Cell::Obstruction,
-                    _ => panic!("unknown character: {}", char),
-                })
-                .collect()
-        })
-        .collect();
+fn parse(input: &str) -> (Guard, Grid<Cell>) {
+    let map = Grid::parse(input, |c| c);
+    let position = map.position(|&c| c == '^').expect("guard not found");
 
-    (guard, grid)
-}
+    let grid = map.map(|&char| match char {
+        '.' | '^' => Cell::Empty,
+        '#' => Cell::Obstruction,
+        _ => panic!("unknown character: {}", char),
+    });
 
-fn analyze_cell_ahead(guard: &Guard, grid: &[Vec<Cell>]) -> Option<Cell> {
-    let (next_x, next_y) = match guard.direction {
-        Direction::Left => (guard.x.checked_sub(1)?, guard.y),
-        Direction::Up => (guard.x, guard.y.checked_sub(1)?),
-        Direction::Right => (guard.x + 1, guard.y),
-        Direction::Down => (guard.x, guard.y + 1),
-    };
+    (
+        Guard {
+            position,
+            direction: Direction::Up,
+        },
+        grid,
+    )
+}
 
-    // include out-of-bounds-check for next_y and next_x
-    grid.get(next_y).and_then(|row| row.get(next_x)).copied()
+fn analyze_cell_ahead(guard: &Guard, grid: &Grid<Cell>) -> Option<Cell> {
+    grid.step(guard.position, guard.direction)
+        .map(|pos| grid[pos])
 }
 
-fn extract_visited_positions(guard: &mut Guard, grid: &Vec<Vec<Cell>>) -> HashSet<(usize, usize)> {
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+fn extract_visited_positions(guard: &mut Guard, grid: &Grid<Cell>) -> HashSet<Point> {
+    let mut visited: HashSet<Point> = HashSet::new();
 
-    visited.insert((guard.x, guard.y));
+    visited.insert(guard.position);
 
     while let Some(cell) = analyze_cell_ahead(guard, grid) {
         if cell == Cell::Obstruction {
             guard.turn_right_90_degrees();
         }
         guard.move_to_next_position();
-        visited.insert((guard.x, guard.y));
+        visited.insert(guard.position);
     }
     visited
 }
 
-fn is_cycling(mut guard: Guard, grid: &[Vec<Cell>]) -> bool {
-    let mut visited = HashSet::new();
-    visited.insert((guard.x, guard.y, guard.direction));
+const NO_OBSTRUCTION: u32 = u32::MAX;
+
+/// For every cell and direction, the coordinate along the row or column at which the guard stops in front of
+/// the next obstruction. Lets the guard move from turn to turn instead of cell by cell.
+struct JumpTable {
+    width: usize,
+    height: usize,
+    /// Indexed by the orthogonal direction, then by the cell in row-major order.
+    stops: [Vec<u32>; 4],
+}
+
+impl JumpTable {
+    fn new(grid: &Grid<Cell>) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let mut stops: [Vec<u32>; 4] =
+            std::array::from_fn(|_| vec![NO_OBSTRUCTION; width * height]);
+        let is_obstruction = |x: usize, y: usize| grid[(x, y)] == Cell::Obstruction;
+
+        // sweep every line once per direction, remembering the last obstruction behind the sweep.
+        // NOTE: an obstruction on the border yields `NO_OBSTRUCTION` through `wrapping_sub`, which is never read.
+        for y in 0..height {
+            let mut stop = NO_OBSTRUCTION;
+            for x in 0..width {
+                if is_obstruction(x, y) {
+                    stop = x as u32 + 1;
+                } else {
+                    stops[Self::slot(Direction::Left)][y * width + x] = stop;
+                }
+            }
+
+            let mut stop = NO_OBSTRUCTION;
+            for x in (0..width).rev() {
+                if is_obstruction(x, y) {
+                    stop = (x as u32).wrapping_sub(1);
+                } else {
+                    stops[Self::slot(Direction::Right)][y * width + x] = stop;
+                }
+            }
+        }
+
+        for x in 0..width {
+            let mut stop = NO_OBSTRUCTION;
+            for y in 0..height {
+                if is_obstruction(x, y) {
+                    stop = y as u32 + 1;
+                } else {
+                    stops[Self::slot(Direction::Up)][y * width + x] = stop;
+                }
+            }
+
+            let mut stop = NO_OBSTRUCTION;
+            for y in (0..height).rev() {
+                if is_obstruction(x, y) {
+                    stop = (y as u32).wrapping_sub(1);
+                } else {
+                    stops[Self::slot(Direction::Down)][y * width + x] = stop;
+                }
+            }
+        }
 
-    loop {
-        match analyze_cell_ahead(&guard, grid) {
-            Some(Cell::Obstruction) => guard.turn_right_90_degrees(),
-            Some(Cell::Empty) => guard.move_to_next_position(),
-            None => return false,
+        JumpTable {
+            width,
+            height,
+            stops,
         }
+    }
+
+    fn slot(direction: Direction) -> usize {
+        debug_assert!(!direction.is_diagonal());
+        direction as usize / 2
+    }
+
+    /// Returns where the guard stops in front of the next obstruction, taking the added `obstruction` into
+    /// account. Returns `None` if the guard leaves the map instead.
+    fn stop(&self, guard: &Guard, obstruction: Point) -> Option<Point> {
+        let Guard {
+            position,
+            direction,
+        } = *guard;
+        let stop = self.stops[Self::slot(direction)][position.y * self.width + position.x];
+        let stop = (stop != NO_OBSTRUCTION).then_some(stop as usize);
+
+        // the added obstruction only matters if it lies ahead of the guard and before the original stop.
+        match direction {
+            Direction::Up if obstruction.x == position.x && obstruction.y < position.y => {
+                Some(stop.map_or(obstruction.y + 1, |y| y.max(obstruction.y + 1)))
+            }
+            Direction::Down if obstruction.x == position.x && obstruction.y > position.y => {
+                Some(stop.map_or(obstruction.y - 1, |y| y.min(obstruction.y - 1)))
+            }
+            Direction::Left if obstruction.y == position.y && obstruction.x < position.x => {
+                Some(stop.map_or(obstruction.x + 1, |x| x.max(obstruction.x + 1)))
+            }
+            Direction::Right if obstruction.y == position.y && obstruction.x > position.x => {
+                Some(stop.map_or(obstruction.x - 1, |x| x.min(obstruction.x - 1)))
+            }
+            _ => stop,
+        }
+        .map(|along| match direction {
+            Direction::Up | Direction::Down => Point::new(position.x, along),
+            _ => Point::new(along, position.y),
+        })
+    }
+
+    /// Moves the guard to the next obstruction and turns it, taking the added `obstruction` into account.
+    /// Returns `None` if the guard leaves the map instead.
+    fn next_turn(&self, guard: &Guard, obstruction: Point) -> Option<Guard> {
+        let mut guard = Guard {
+            position: self.stop(guard, obstruction)?,
+            ..*guard
+        };
+        guard.turn_right_90_degrees();
+        Some(guard)
+    }
+
+    /// Whether the guard ends up walking in a loop once `obstruction` is added to the map.
+    /// Only the states in which the guard turns are recorded, a loop repeats one of them.
+    fn is_looping(
+        &self,
+        initial_guard: &Guard,
+        obstruction: Point,
+        visited: &mut StateSet,
+    ) -> bool {
+        visited.clear();
+        cycle::repeats(
+            *initial_guard,
+            |guard| self.next_turn(guard, obstruction),
+            |guard| visited.insert(self.state_index(guard)),
+        )
+    }
+
+    fn state_index(&self, guard: &Guard) -> usize {
+        (guard.position.y * self.width + guard.position.x) * 4 + Self::slot(guard.direction)
+    }
+
+    fn state_count(&self) -> usize {
+        self.width * self.height * 4
+    }
+}
 
-        if !visited.insert((guard.x, guard.y, guard.direction)) {
-            return true;
+/// A bitset of guard states, keyed by position and direction.
+struct StateSet {
+    words: Vec<u64>,
+}
+
+impl StateSet {
+    fn new(len: usize) -> Self {
+        StateSet {
+            words: vec![0; len.div_ceil(64)],
         }
     }
+
+    /// Adds the state, returning whether it was not in the set before.
+    fn insert(&mut self, index: usize) -> bool {
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let is_new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        is_new
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
 }
 
-fn count_cycle_causing_positions(
+fn find_cycle_causing_positions(
     initial_guard: &Guard,
-    original_grid: &[Vec<Cell>],
-    visited_positions: &HashSet<(usize, usize)>
-) -> u32 {
+    original_grid: &Grid<Cell>,
+    visited_positions: &HashSet<Point>,
+) -> Vec<Point> {
+    let jump_table = JumpTable::new(original_grid);
+
     visited_positions
         .par_iter()
-        .filter(|&&pos| pos != (initial_guard.x, initial_guard.y))
-        .map(|&pos| creates_cycle(pos, initial_guard, original_grid))
-        .filter(|&creates_cycle| creates_cycle)
-        .count() as u32
+        .filter(|&&pos| pos != initial_guard.position)
+        .map_init(
+            || StateSet::new(jump_table.state_count()),
+            |visited, &pos| {
+                jump_table
+                    .is_looping(initial_guard, pos, visited)
+                    .then_some(pos)
+            },
+        )
+        .flatten()
+        .collect()
 }
 
-fn creates_cycle(
-    (x, y): (usize, usize),
-    initial_guard: &Guard,
-    original_grid: &[Vec<Cell>]
-) -> bool {
-    let mut modified_grid = original_grid.to_vec();
-    modified_grid[y][x] = Cell::Obstruction;
-    is_cycling(*initial_guard, &modified_grid)
+#[cfg(feature = "visualize")]
+fn draw_walk(grid: &Grid<Cell>, guard: &Guard, visited: &HashSet<Point>) -> visualize::Frame {
+    let symbol = match guard.direction {
+        Direction::Up => '^',
+        Direction::Right => '>',
+        Direction::Down => 'v',
+        _ => '<',
+    };
+
+    visualize::Frame::new(grid, |cell| match cell {
+        Cell::Obstruction => '#',
+        Cell::Empty => '.',
+    })
+    .highlight(visited.iter().copied(), Color::Blue)
+    .marker(guard.position, symbol, Color::Yellow)
+    .caption(format!("visited: {}", visited.len()))
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (mut guard, grid) = parse(input);
-    let visited = extract_visited_positions(&mut guard, &grid);
-    Some(visited.len() as u32)
+#[cfg(feature = "visualize")]
+fn paint_walk(grid: &Grid<Cell>, guard: &Guard, visited: &HashSet<Point>) -> render::Image {
+    render::Image::new(grid, |cell| match cell {
+        Cell::Obstruction => Color::Gray.into(),
+        Cell::Empty => [16, 16, 32],
+    })
+    .paint(visited.iter().copied(), Color::Blue)
+    .paint([guard.position], Color::Yellow)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (initial_guard, original_grid) = parse(input);
-    let visited_positions = extract_visited_positions(&mut initial_guard.clone(), &original_grid);
+/// Replays the walk of part one, showing a frame and recording an image whenever the guard turns.
+#[cfg(feature = "visualize")]
+fn visualize_patrol(initial_guard: &Guard, grid: &Grid<Cell>) {
+    let mut guard = *initial_guard;
+    let mut visited = HashSet::from([guard.position]);
+    let mut patrol = render::Animation::new();
 
-    let cycle_causing_positions = count_cycle_causing_positions(
-        &initial_guard,
-        &original_grid,
-        &visited_positions
-    );
+    while let Some(cell) = analyze_cell_ahead(&guard, grid) {
+        if cell == Cell::Obstruction {
+            guard.turn_right_90_degrees();
+            visualize::show(&draw_walk(grid, &guard, &visited));
+            if render::is_enabled() {
+                patrol.push(paint_walk(grid, &guard, &visited));
+            }
+        }
+        guard.move_to_next_position();
+        visited.insert(guard.position);
+    }
+
+    visualize::show(&draw_walk(grid, &guard, &visited));
+    patrol.push(paint_walk(grid, &guard, &visited));
+    patrol.save(DAY, "patrol");
+}
 
-    Some(cycle_causing_positions)
+pub fn part_one((guard, grid): &(Guard, Grid<Cell>)) -> Option<u32> {
+    let visited = extract_visited_positions(&mut guard.clone(), grid);
+    advent_of_code::visualize! {
+        visualize_patrol(guard, grid);
+    }
+    Some(visited.len() as u32)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub fn part_two((initial_guard, original_grid): &(Guard, Grid<Cell>)) -> Option<u32> {
+    let visited_positions = extract_visited_positions(&mut initial_guard.clone(), original_grid);
+
+    let cycle_causing_positions =
+        find_cycle_causing_positions(initial_guard, original_grid, &visited_positions);
+
+    advent_of_code::visualize! {
+        let frame = draw_walk(original_grid, initial_guard, &visited_positions)
+            .highlight(cycle_causing_positions.iter().copied(), Color::Red)
+            .caption(format!("obstructions that cause a loop: {}", cycle_causing_positions.len()));
+        visualize::show(&frame);
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(41));
+        paint_walk(original_grid, initial_guard, &visited_positions)
+            .paint(cycle_causing_positions.iter().copied(), Color::Red)
+            .save(DAY, "obstructions");
     }
 
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6));
+    Some(cycle_causing_positions.len() as u32)
+}
+
+advent_of_code::examples! {
+    parse = parse,
+    part_one {
+        example => Some(41),
+    }
+    part_two {
+        example => Some(6),
     }
 }
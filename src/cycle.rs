@@ -0,0 +1,186 @@
+//! Cycle detection for simulations that repeat themselves.
+//!
+//! A simulation is described by its initial state and a `step` function that returns the next state, or `None`
+//! if the simulation ends. Simulations that never end simply always return `Some`.
+use std::{collections::HashMap, hash::Hash};
+
+/// Describes where a sequence of states starts repeating itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Number of steps before the first state of the cycle is reached.
+    pub start: usize,
+    /// Number of steps after which a state of the cycle repeats.
+    pub length: usize,
+}
+
+/// Finds the cycle of a simulation by remembering every state it visits.
+/// Returns `None` if the simulation ends.
+pub fn find_cycle<S: Clone + Eq + Hash>(
+    initial: S,
+    step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let (states, repeated) = run_until_repeat(initial, usize::MAX, step);
+    repeated.map(|start| Cycle {
+        start,
+        length: states.len() - start,
+    })
+}
+
+/// Finds the cycle of a simulation with Brent's algorithm, which only keeps two states in memory at the cost of
+/// calling `step` up to three times as often as [`find_cycle`]. Returns `None` if the simulation ends.
+///
+/// See: Brent, "An improved Monte Carlo factorization algorithm" (1980).
+pub fn find_cycle_brent<S: Clone + Eq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    // find the cycle length by moving the tortoise to the hare at every power of two.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    // find the cycle start by moving both with `length` steps distance until they meet.
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Returns the state after `steps` steps, skipping ahead once the simulation repeats itself.
+/// Returns `None` if the simulation ends before.
+pub fn fast_forward<S: Clone + Eq + Hash>(
+    initial: S,
+    steps: usize,
+    step: impl FnMut(&S) -> Option<S>,
+) -> Option<S> {
+    let (mut states, repeated) = run_until_repeat(initial, steps, step);
+
+    let index = match repeated {
+        Some(start) => start + (steps - start) % (states.len() - start),
+        None if steps < states.len() => steps,
+        None => return None,
+    };
+
+    Some(states.swap_remove(index))
+}
+
+/// Whether the simulation repeats a state before it ends. `visit` records a state and returns whether it was not
+/// visited before, so that states can be kept in whatever set suits the simulation, e.g. a bitset of state indices.
+pub fn repeats<S>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+    mut visit: impl FnMut(&S) -> bool,
+) -> bool {
+    let mut state = initial;
+    loop {
+        if !visit(&state) {
+            return true;
+        }
+        match step(&state) {
+            Some(next) => state = next,
+            None => return false,
+        }
+    }
+}
+
+/// Runs the simulation until a state repeats or `max_steps` steps were taken.
+/// Returns the distinct states in order, and the index of the repeated state if one was found.
+fn run_until_repeat<S: Clone + Eq + Hash>(
+    initial: S,
+    max_steps: usize,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> (Vec<S>, Option<usize>) {
+    let mut indices = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+
+    while states.len() <= max_steps {
+        let Some(next) = step(&states[states.len() - 1]) else {
+            break;
+        };
+
+        if let Some(&start) = indices.get(&next) {
+            return (states, Some(start));
+        }
+
+        indices.insert(next.clone(), states.len());
+        states.push(next);
+    }
+
+    (states, None)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{fast_forward, find_cycle, find_cycle_brent, repeats, Cycle};
+    use std::collections::HashSet;
+
+    /// 0, 1, ..., 4, then cycles through 5, 6, 7.
+    fn step(x: &u32) -> Option<u32> {
+        Some(if *x == 7 { 5 } else { x + 1 })
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let expected = Some(Cycle {
+            start: 5,
+            length: 3,
+        });
+        assert_eq!(find_cycle(0, step), expected);
+        assert_eq!(find_cycle_brent(0, step), expected);
+        assert_eq!(
+            find_cycle_brent(6, step),
+            Some(Cycle {
+                start: 0,
+                length: 3
+            })
+        );
+    }
+
+    #[test]
+    fn handles_ending_simulations() {
+        let step = |x: &u32| (*x < 10).then_some(x + 1);
+        assert_eq!(find_cycle(0, step), None);
+        assert_eq!(find_cycle_brent(0, step), None);
+        assert!(!repeats(0, step, |_| true));
+        assert_eq!(fast_forward(0, 10, step), Some(10));
+        assert_eq!(fast_forward(0, 11, step), None);
+    }
+
+    #[test]
+    fn detects_repeated_states() {
+        let mut visited = HashSet::new();
+        assert!(repeats(0, step, |x| visited.insert(*x)));
+        assert_eq!(visited.len(), 8);
+    }
+
+    #[test]
+    fn fast_forwards() {
+        assert_eq!(fast_forward(0, 0, step), Some(0));
+        assert_eq!(fast_forward(0, 4, step), Some(4));
+        assert_eq!(fast_forward(0, 8, step), Some(5));
+        assert_eq!(fast_forward(0, 1_000_000_000, step), Some(7));
+    }
+}
@@ -0,0 +1,318 @@
+//! Points, vectors and directions on a two-dimensional grid.
+//!
+//! The `y` axis points down, matching the layout of puzzle inputs: `Direction::Up` decreases `y`.
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position on a grid, or the difference between two positions.
+/// Unsigned points index into grids, signed points describe steps and unbounded positions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T = usize> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A step between two points.
+pub type Vec2<T = isize> = Point<T>;
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: Copy + PartialOrd + Sub<Output = T>> Point<T> {
+    /// Returns `|a - b|` without underflowing unsigned coordinates.
+    fn abs_diff(a: T, b: T) -> T {
+        if a > b {
+            a - b
+        } else {
+            b - a
+        }
+    }
+
+    /// Number of orthogonal steps between two points.
+    pub fn manhattan(self, other: Self) -> T
+    where
+        T: Add<Output = T>,
+    {
+        Self::abs_diff(self.x, other.x) + Self::abs_diff(self.y, other.y)
+    }
+
+    /// Number of steps between two points if diagonal steps are allowed.
+    pub fn chebyshev(self, other: Self) -> T {
+        let (dx, dy) = (
+            Self::abs_diff(self.x, other.x),
+            Self::abs_diff(self.y, other.y),
+        );
+        if dx > dy {
+            dx
+        } else {
+            dy
+        }
+    }
+}
+
+impl Point<usize> {
+    /// Moves the point by `step`. Returns `None` if a coordinate would become negative.
+    pub fn checked_add_signed(self, step: impl Into<Vec2>) -> Option<Self> {
+        let step = step.into();
+        Some(Point::new(
+            self.x.checked_add_signed(step.x)?,
+            self.y.checked_add_signed(step.y)?,
+        ))
+    }
+
+    /// Moves the point by `step`, only if it stays within a `width` x `height` area.
+    pub fn step_within(self, step: impl Into<Vec2>, width: usize, height: usize) -> Option<Self> {
+        self.checked_add_signed(step)
+            .filter(|p| p.x < width && p.y < height)
+    }
+
+    /// Converts the point to signed coordinates.
+    ///
+    /// # Panics
+    /// Panics if a coordinate exceeds `isize::MAX`.
+    pub fn signed(self) -> Point<isize> {
+        Point::new(
+            isize::try_from(self.x).expect("x coordinate exceeds isize::MAX"),
+            isize::try_from(self.y).expect("y coordinate exceeds isize::MAX"),
+        )
+    }
+}
+
+impl Point<isize> {
+    /// Converts the point to unsigned coordinates. Returns `None` if a coordinate is negative.
+    pub fn unsigned(self) -> Option<Point<usize>> {
+        Some(Point::new(
+            usize::try_from(self.x).ok()?,
+            usize::try_from(self.y).ok()?,
+        ))
+    }
+
+    /// Iterates over the four orthogonal neighbors of the point.
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        Direction::ORTHOGONAL.into_iter().map(move |d| self + d)
+    }
+
+    /// Iterates over all eight neighbors of the point, including diagonal ones.
+    pub fn all_neighbors(self) -> impl Iterator<Item = Self> {
+        Direction::ALL.into_iter().map(move |d| self + d)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(value: Point<T>) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl From<Direction> for Vec2 {
+    fn from(value: Direction) -> Self {
+        value.step()
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Add<Direction> for Point<isize> {
+    type Output = Self;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        self + rhs.step()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// One of the eight directions on a grid, ordered clockwise starting at `Up`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    #[default]
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// The four orthogonal directions, clockwise starting at `Up`.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// All eight directions, clockwise starting at `Up`.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// Rotates the direction clockwise by `eighths` of a full turn.
+    fn rotate(self, eighths: usize) -> Self {
+        Self::ALL[(self as usize + eighths) % 8]
+    }
+
+    /// Turns 90 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Turns 90 degrees counterclockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// Turns 45 degrees clockwise.
+    pub fn turn_right_45(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Turns 45 degrees counterclockwise.
+    pub fn turn_left_45(self) -> Self {
+        self.rotate(7)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    /// The step of length one in this direction.
+    pub fn step(self) -> Vec2 {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::UpRight => Point::new(1, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::DownRight => Point::new(1, 1),
+            Direction::Down => Point::new(0, 1),
+            Direction::DownLeft => Point::new(-1, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::UpLeft => Point::new(-1, -1),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Direction, Point, Vec2};
+
+    #[test]
+    fn turns_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::UpLeft.turn_right_45(), Direction::Up);
+        assert_eq!(Direction::DownRight.opposite(), Direction::UpLeft);
+        assert!(Direction::ORTHOGONAL.iter().all(|d| !d.is_diagonal()));
+        assert!(Direction::ALL
+            .iter()
+            .all(|&d| d.step() == -d.opposite().step()));
+    }
+
+    #[test]
+    fn steps_within_bounds() {
+        let origin = Point::new(0_usize, 0);
+        assert_eq!(origin.checked_add_signed(Direction::Up), None);
+        assert_eq!(
+            origin.checked_add_signed(Direction::DownRight),
+            Some(Point::new(1, 1))
+        );
+        assert_eq!(
+            Point::new(2_usize, 1).step_within(Direction::Right, 3, 3),
+            None
+        );
+        assert_eq!(
+            Point::new(2_usize, 1).step_within(Vec2::new(-2, 1), 3, 3),
+            Some(Point::new(0, 2))
+        );
+    }
+
+    #[test]
+    fn measures_distances() {
+        let a = Point::new(1_usize, 5);
+        let b = Point::new(4_usize, 1);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(Point::new(-2_i64, 3).manhattan(Point::new(2, -3)), 10);
+    }
+
+    #[test]
+    fn converts_points() {
+        let point = Point::new(3_usize, 4);
+        assert_eq!(point.signed() + Direction::Left, Point::new(2, 4));
+        assert_eq!(Point::new(-1_isize, 0).unsigned(), None);
+        assert_eq!(<(usize, usize)>::from(point), (3, 4));
+        assert_eq!(point * 2 - Point::new(1, 1), Point::new(5, 7));
+        assert_eq!(Point::new(0_isize, 0).neighbors().count(), 4);
+        assert!(Point::new(0_isize, 0)
+            .all_neighbors()
+            .all(|p| p.chebyshev(Point::new(0, 0)) == 1));
+    }
+}
@@ -0,0 +1,529 @@
+//! Graph algorithms: an adjacency-list [`Graph`] for explicit graphs, and searches over implicit graphs whose
+//! edges are produced by a `neighbors` closure.
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::Add,
+};
+
+/// A directed graph stored as adjacency lists. Nodes keep the order in which they were added.
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<Vec<usize>>,
+}
+
+/// The nodes of a cycle that prevents a topological sort, in edge order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N: Debug> Display for Cycle<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph contains a cycle: ")?;
+        for node in &self.0 {
+            write!(f, "{node:?} -> ")?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{first:?}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N: Debug> std::error::Error for Cycle<N> {}
+
+impl<N> Default for Graph<N> {
+    fn default() -> Self {
+        Graph {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node without edges, if it is not part of the graph yet.
+    pub fn add_node(&mut self, node: N) {
+        self.index_of(node);
+    }
+
+    /// Adds an edge, adding its nodes if they are not part of the graph yet.
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let from = self.index_of(from);
+        let to = self.index_of(to);
+        if !self.edges[from].contains(&to) {
+            self.edges[from].push(to);
+        }
+    }
+
+    fn index_of(&mut self, node: N) -> usize {
+        if let Some(&index) = self.indices.get(&node) {
+            return index;
+        }
+        self.nodes.push(node.clone());
+        self.edges.push(Vec::new());
+        self.indices.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.iter()
+    }
+
+    /// Iterates over the nodes that `node` has an edge to.
+    pub fn neighbors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.indices
+            .get(node)
+            .map(|&i| self.edges[i].as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|&i| &self.nodes[i])
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> {
+        self.edges.iter().enumerate().flat_map(move |(from, to)| {
+            to.iter()
+                .map(move |&to| (&self.nodes[from], &self.nodes[to]))
+        })
+    }
+
+    /// Returns the graph induced by `nodes`: the given nodes, in the given order, and all edges between them.
+    /// Nodes that are not part of this graph are added without edges.
+    pub fn subgraph(&self, nodes: impl IntoIterator<Item = N>) -> Self {
+        let mut subgraph = Graph::new();
+        for node in nodes {
+            subgraph.add_node(node);
+        }
+
+        for (index, node) in subgraph.nodes.iter().enumerate() {
+            if let Some(&i) = self.indices.get(node) {
+                subgraph.edges[index] = self.edges[i]
+                    .iter()
+                    .filter_map(|&to| subgraph.indices.get(&self.nodes[to]).copied())
+                    .collect();
+            }
+        }
+
+        subgraph
+    }
+
+    /// Orders the nodes so that every edge points from an earlier to a later node.
+    /// Of the nodes that could come next, the one that was added first is taken, so nodes that are not ordered
+    /// relative to each other keep the order in which they were added.
+    ///
+    /// # Errors
+    /// Returns one of the cycles of the graph if it contains any.
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        let mut in_degrees = vec![0; self.len()];
+        for &to in self.edges.iter().flatten() {
+            in_degrees[to] += 1;
+        }
+
+        // Kahn's algorithm, with a min-heap of insertion indices instead of a queue to keep the order stable.
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.len())
+            .filter(|&i| in_degrees[i] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+
+        while let Some(Reverse(index)) = ready.pop() {
+            order.push(self.nodes[index].clone());
+            for &to in &self.edges[index] {
+                in_degrees[to] -= 1;
+                if in_degrees[to] == 0 {
+                    ready.push(Reverse(to));
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&in_degrees))
+        }
+    }
+
+    /// Finds a cycle among the nodes that a topological sort could not remove.
+    /// Each of them still has an incoming edge from another one, so walking these edges backwards
+    /// eventually revisits a node.
+    fn find_cycle(&self, in_degrees: &[usize]) -> Cycle<N> {
+        let mut predecessors = vec![None; self.len()];
+        for (from, to) in self.edges.iter().enumerate() {
+            if in_degrees[from] > 0 {
+                for &to in to.iter().filter(|&&to| in_degrees[to] > 0) {
+                    predecessors[to] = Some(from);
+                }
+            }
+        }
+
+        let mut seen = vec![false; self.len()];
+        let mut index = (0..self.len())
+            .find(|&i| in_degrees[i] > 0)
+            .expect("an unsorted graph has nodes left.");
+
+        while !seen[index] {
+            seen[index] = true;
+            index = predecessors[index].expect("every remaining node has a predecessor.");
+        }
+
+        let start = index;
+        let mut cycle = vec![self.nodes[start].clone()];
+        index = predecessors[start].expect("every remaining node has a predecessor.");
+        while index != start {
+            cycle.push(self.nodes[index].clone());
+            index = predecessors[index].expect("every remaining node has a predecessor.");
+        }
+
+        cycle.reverse();
+        Cycle(cycle)
+    }
+
+    /// Partitions the nodes into strongly connected components, i.e. maximal sets of nodes that can all reach
+    /// each other. Components are returned in reverse topological order: no component has an edge to a
+    /// component that follows it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        // Tarjan's algorithm, with an explicit stack of `(node, next edge)` frames instead of recursion.
+        let mut next_index = 0;
+        let mut indices: Vec<Option<usize>> = vec![None; self.len()];
+        let mut low_links = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+
+        for root in 0..self.len() {
+            if indices[root].is_some() {
+                continue;
+            }
+
+            let mut frames = vec![(root, 0)];
+
+            while let Some(&mut (node, ref mut next_edge)) = frames.last_mut() {
+                if *next_edge == 0 {
+                    indices[node] = Some(next_index);
+                    low_links[node] = next_index;
+                    next_index += 1;
+                    stack.push(node);
+                    on_stack[node] = true;
+                }
+
+                if let Some(&to) = self.edges[node].get(*next_edge) {
+                    *next_edge += 1;
+                    match indices[to] {
+                        None => frames.push((to, 0)),
+                        Some(index) if on_stack[to] => {
+                            low_links[node] = low_links[node].min(index);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                frames.pop();
+
+                if let Some(&(parent, _)) = frames.last() {
+                    low_links[parent] = low_links[parent].min(low_links[node]);
+                }
+
+                if Some(low_links[node]) == indices[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(self.nodes[member].clone());
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+}
+
+impl<N: Clone + Eq + Hash> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<T: IntoIterator<Item = (N, N)>>(edges: T) -> Self {
+        let mut graph = Graph::new();
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Returns the number of steps from `start` to every state reachable from it.
+pub fn bfs<S, I>(start: S, mut neighbors: impl FnMut(&S) -> I) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((state, distance)) = queue.pop_front() {
+        for next in neighbors(&state) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Returns a path with the fewest steps from `start` to a state that satisfies `is_goal`, including both ends.
+pub fn bfs_path<S, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Vec<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut states = vec![start.clone()];
+    let mut parents = vec![None];
+    let mut seen = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(index) = queue.pop_front() {
+        if is_goal(&states[index]) {
+            return Some(path_to(index, &states, &parents));
+        }
+
+        for next in neighbors(&states[index]) {
+            if !seen.contains_key(&next) {
+                seen.insert(next.clone(), states.len());
+                queue.push_back(states.len());
+                states.push(next);
+                parents.push(Some(index));
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns all states reachable from `start` in depth-first order, starting with `start`.
+pub fn dfs<S, I>(start: S, mut neighbors: impl FnMut(&S) -> I) -> Vec<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut seen = HashSet::new();
+    let mut order = Vec::new();
+    let mut stack = vec![start];
+
+    while let Some(state) = stack.pop() {
+        if !seen.insert(state.clone()) {
+            continue;
+        }
+
+        let mut next: Vec<S> = neighbors(&state)
+            .into_iter()
+            .filter(|next| !seen.contains(next))
+            .collect();
+        // visit neighbors in the order they were returned.
+        next.reverse();
+        stack.extend(next);
+        order.push(state);
+    }
+
+    order
+}
+
+/// Returns the cost and a cheapest path from `start` to a state that satisfies `is_goal`.
+/// `neighbors` returns the states that can be reached from a state together with the cost of getting there.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+/// Like [`dijkstra`], but explores states in order of their cost plus `heuristic`, which estimates the remaining
+/// cost to a goal. The result is only guaranteed to be the cheapest if `heuristic` never overestimates.
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut indices = HashMap::from([(start.clone(), 0)]);
+    let mut states = vec![start];
+    let mut costs = vec![C::default()];
+    let mut parents = vec![None];
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        // a cheaper way to this state was found after this entry was queued.
+        if cost > costs[index] {
+            continue;
+        }
+
+        if is_goal(&states[index]) {
+            return Some((cost, path_to(index, &states, &parents)));
+        }
+
+        for (next, step_cost) in neighbors(&states[index]) {
+            let next_cost = cost + step_cost;
+
+            let next_index = match indices.get(&next) {
+                Some(&i) if costs[i] <= next_cost => continue,
+                Some(&i) => {
+                    costs[i] = next_cost;
+                    parents[i] = Some(index);
+                    i
+                }
+                None => {
+                    indices.insert(next.clone(), states.len());
+                    states.push(next);
+                    costs.push(next_cost);
+                    parents.push(Some(index));
+                    states.len() - 1
+                }
+            };
+
+            let estimate = next_cost + heuristic(&states[next_index]);
+            heap.push(Reverse((estimate, next_cost, next_index)));
+        }
+    }
+
+    None
+}
+
+fn path_to<S: Clone>(mut index: usize, states: &[S], parents: &[Option<usize>]) -> Vec<S> {
+    let mut path = vec![states[index].clone()];
+    while let Some(parent) = parents[index] {
+        path.push(states[parent].clone());
+        index = parent;
+    }
+    path.reverse();
+    path
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{astar, bfs, bfs_path, dfs, dijkstra, Cycle, Graph};
+    use crate::{geom::Point, grid::Grid};
+
+    #[test]
+    fn sorts_topologically() {
+        let graph: Graph<u32> = [(97, 13), (97, 47), (75, 47), (47, 13), (75, 13)]
+            .into_iter()
+            .collect();
+        assert_eq!(graph.topological_sort(), Ok(vec![97, 75, 47, 13]));
+
+        let page = graph.subgraph([13, 75, 99]);
+        assert_eq!(page.topological_sort(), Ok(vec![75, 13, 99]));
+
+        let mut graph = Graph::new();
+        graph.add_node('x');
+        graph.add_node('z');
+        graph.add_node('y');
+        graph.add_edge('x', 'z');
+        assert_eq!(graph.topological_sort(), Ok(vec!['x', 'z', 'y']));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let graph: Graph<char> = [('a', 'b'), ('b', 'c'), ('c', 'd'), ('d', 'b')]
+            .into_iter()
+            .collect();
+        let Err(Cycle(cycle)) = graph.topological_sort() else {
+            panic!("expected a cycle.");
+        };
+        assert_eq!(cycle.len(), 3);
+        for (i, node) in cycle.iter().enumerate() {
+            let next = &cycle[(i + 1) % cycle.len()];
+            assert!(graph.neighbors(node).any(|n| n == next));
+        }
+    }
+
+    #[test]
+    fn finds_strongly_connected_components() {
+        let graph: Graph<char> = [
+            ('a', 'b'),
+            ('b', 'c'),
+            ('c', 'a'),
+            ('c', 'd'),
+            ('d', 'e'),
+            ('e', 'd'),
+            ('f', 'e'),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut components = graph.strongly_connected_components();
+        components.iter_mut().for_each(|c| c.sort_unstable());
+        assert_eq!(
+            components,
+            vec![vec!['d', 'e'], vec!['a', 'b', 'c'], vec!['f']]
+        );
+    }
+
+    #[test]
+    fn searches_grids() {
+        let grid = Grid::parse("S.#\n..#\n#.E\n", |c| c);
+        let start = grid.position(|&c| c == 'S').unwrap();
+        let end = grid.position(|&c| c == 'E').unwrap();
+        let open = |pos: &Point| {
+            grid.neighbors(*pos)
+                .filter(|(_, &c)| c != '#')
+                .map(|(pos, _)| pos)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(bfs(start, open)[&end], 4);
+        assert_eq!(bfs_path(start, open, |&pos| pos == end).unwrap().len(), 5);
+        assert_eq!(dfs(start, open).len(), 6);
+        assert_eq!(bfs_path(start, open, |&pos| pos == Point::new(2, 0)), None);
+
+        let weighted = |pos: &Point| open(pos).into_iter().map(|next| (next, next.x + 1));
+        let (cost, path) = dijkstra(start, weighted, |&pos| pos == end).unwrap();
+        assert_eq!(cost, 8);
+        assert_eq!(
+            path,
+            astar(start, weighted, |pos| pos.manhattan(end), |&pos| pos == end)
+                .unwrap()
+                .1
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! A rectangular, row-major grid for puzzles that come as a character map.
+//!
+//! Positions are [`Point`]s, where `x` is the column and `y` is the row, starting at the top left corner.
+//! Methods that take a position also accept `(x, y)` tuples.
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use ndarray::Array2;
+
+use crate::geom::{Direction, Point, Vec2};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid of the given size with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a character map, converting every character with `f`.
+    /// Trailing whitespace of the input is ignored.
+    ///
+    /// # Panics
+    /// Panics if the lines of the map differ in length.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(input.len());
+
+        for (y, line) in input.trim_end().lines().enumerate() {
+            let len = cells.len();
+            cells.extend(line.chars().map(&mut f));
+
+            let line_width = cells.len() - len;
+            let width = *width.get_or_insert(line_width);
+            assert_eq!(
+                width,
+                line_width,
+                "line {} of the grid has {line_width} characters, expected {width}.",
+                y + 1
+            );
+
+            height += 1;
+        }
+
+        Grid {
+            width: width.unwrap_or_default(),
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, pos: impl Into<Point>) -> bool {
+        let pos = pos.into();
+        pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn get(&self, pos: impl Into<Point>) -> Option<&T> {
+        let pos = pos.into();
+        self.in_bounds(pos)
+            .then(|| &self.cells[pos.y * self.width + pos.x])
+    }
+
+    pub fn get_mut(&mut self, pos: impl Into<Point>) -> Option<&mut T> {
+        let pos = pos.into();
+        self.in_bounds(pos)
+            .then(|| &mut self.cells[pos.y * self.width + pos.x])
+    }
+
+    /// Returns the position reached by moving `step` away from `pos`, if it lies within the grid.
+    /// `step` is either a [`Direction`] or a [`Vec2`].
+    pub fn step(&self, pos: impl Into<Point>, step: impl Into<Vec2>) -> Option<Point> {
+        pos.into().step_within(step, self.width, self.height)
+    }
+
+    /// Iterates over all positions and cells, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Point::new(i % width, i / width), cell))
+    }
+
+    /// Returns the position of the first cell that matches `predicate`, row by row.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.positions(predicate).next()
+    }
+
+    /// Iterates over the positions of all cells that match `predicate`, row by row.
+    pub fn positions<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Point> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Iterates over the up to four orthogonal neighbors of `pos`.
+    pub fn neighbors(&self, pos: impl Into<Point>) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbors_in(pos.into(), &Direction::ORTHOGONAL)
+    }
+
+    /// Iterates over the up to eight neighbors of `pos`, including diagonal ones.
+    pub fn all_neighbors(&self, pos: impl Into<Point>) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbors_in(pos.into(), &Direction::ALL)
+    }
+
+    fn neighbors_in<'a>(
+        &'a self,
+        pos: Point,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = (Point, &'a T)> {
+        directions
+            .iter()
+            .filter_map(move |&direction| self.step(pos, direction))
+            .map(|pos| (pos, &self[pos]))
+    }
+
+    /// Iterates over the cells starting at `pos` and moving `step` at a time, until leaving the grid.
+    pub fn ray(
+        &self,
+        pos: impl Into<Point>,
+        step: impl Into<Vec2>,
+    ) -> impl Iterator<Item = (Point, &T)> {
+        let (pos, step) = (pos.into(), step.into());
+        std::iter::successors(self.in_bounds(pos).then_some(pos), move |&pos| {
+            self.step(pos, step)
+        })
+        .map(|pos| (pos, &self[pos]))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // NOTE: `chunks` panics for a chunk size of zero.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.ray((x, 0), Direction::Down).map(|(_, cell)| cell)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Iterates over all diagonals running from the top left to the bottom right,
+    /// starting at the bottom left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (1..self.height)
+            .rev()
+            .map(|y| (0, y))
+            .chain((0..self.width).map(|x| (x, 0)));
+        starts.map(|pos| self.ray(pos, Direction::DownRight).map(|(_, cell)| cell))
+    }
+
+    /// Iterates over all diagonals running from the top right to the bottom left,
+    /// starting at the top left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.width)
+            .map(|x| (x, 0))
+            .chain((1..self.height).map(|y| (self.width.saturating_sub(1), y)));
+        starts.map(|pos| self.ray(pos, Direction::DownLeft).map(|(_, cell)| cell))
+    }
+
+    /// Converts every cell with `f`, keeping the shape of the grid.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T, P: Into<Point>> Index<P> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: P) -> &Self::Output {
+        let pos = pos.into();
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "position {pos:?} is out of bounds for a {}x{} grid.",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T, P: Into<Point>> IndexMut<P> for Grid<T> {
+    fn index_mut(&mut self, pos: P) -> &mut Self::Output {
+        let pos = pos.into();
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| {
+            panic!("position {pos:?} is out of bounds for a {width}x{height} grid.")
+        })
+    }
+}
+
+/// Converts nested rows, e.g. a `Vec<Vec<Cell>>` built while parsing.
+///
+/// # Panics
+/// Panics if the rows differ in length.
+impl<T> From<Vec<Vec<T>>> for Grid<T> {
+    fn from(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "rows of the grid differ in length."
+        );
+
+        Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+}
+
+/// Converts an `ndarray` matrix, whose first axis is the row.
+impl<T: Clone> From<&Array2<T>> for Grid<T> {
+    fn from(array: &Array2<T>) -> Self {
+        let (height, width) = array.dim();
+        Grid {
+            width,
+            height,
+            cells: array.iter().cloned().collect(),
+        }
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Grid;
+    use crate::geom::{Direction, Point};
+
+    const MAP: &str = "#.^\n...\n.#.\n.#.\n";
+
+    #[test]
+    fn parses_char_maps() {
+        let grid = Grid::parse(MAP, |c| c == '#');
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid[(0, 0)], true);
+        assert_eq!(grid[(1, 2)], true);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(
+            Grid::parse(MAP, |c| c).position(|&c| c == '^'),
+            Some(Point::new(2, 0))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_ragged_maps() {
+        Grid::parse("...\n..\n", |c| c);
+    }
+
+    #[test]
+    fn finds_neighbors() {
+        let grid = Grid::parse(MAP, |c| c);
+        let corner: Vec<_> = grid.neighbors((0, 0)).map(|(pos, _)| pos).collect();
+        assert_eq!(corner, vec![Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.all_neighbors((1, 1)).count(), 8);
+        assert_eq!(grid.all_neighbors((2, 3)).count(), 3);
+        assert_eq!(grid.step((0, 0), Direction::Left), None);
+        assert_eq!(grid.step((0, 0), (1, 1)), Some(Point::new(1, 1)));
+    }
+
+    #[test]
+    fn iterates_lines() {
+        let grid = Grid::parse("abc\ndef\n", |c| c);
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+        let columns: Vec<String> = grid.columns().map(Iterator::collect).collect();
+        let diagonals: Vec<String> = grid.diagonals().map(Iterator::collect).collect();
+        let anti_diagonals: Vec<String> = grid.anti_diagonals().map(Iterator::collect).collect();
+
+        assert_eq!(rows, ["abc", "def"]);
+        assert_eq!(columns, ["ad", "be", "cf"]);
+        assert_eq!(diagonals, ["d", "ae", "bf", "c"]);
+        assert_eq!(anti_diagonals, ["a", "bd", "ce", "f"]);
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    fn converts_other_representations() {
+        let grid = Grid::parse("abc\ndef\n", |c| c);
+        let rows = vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']];
+        let array = ndarray::arr2(&[['a', 'b', 'c'], ['d', 'e', 'f']]);
+        assert_eq!(Grid::from(rows), grid);
+        assert_eq!(Grid::from(&array), grid);
+    }
+}
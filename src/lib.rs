@@ -1,3 +1,11 @@
+pub mod cycle;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod parse;
+#[cfg(feature = "visualize")]
+pub mod render;
 pub mod template;
+pub mod visualize;
 
 // Use this file to add helper functions and additional modules.
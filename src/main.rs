@@ -1,18 +1,30 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, download, examples, read, scaffold, solve, stars, time,
+};
+use advent_of_code::template::memory::PeakAlloc;
+use advent_of_code::template::Executor;
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
-#[cfg(feature = "today")]
-use std::process;
+use advent_of_code::template::commands::today;
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::commands::solve::Visualization;
+    use advent_of_code::template::commands::time::{BaselineOptions, CheckOptions};
+    use advent_of_code::template::{parse_tolerance, Day, STORED_BASELINE};
+    use std::{process, time::Duration};
+
+    /// Part timeout in seconds for commands that run several days, unless overridden with `--timeout`.
+    const DEFAULT_TIMEOUT_SECS: u64 = 60;
 
     pub enum AppArguments {
         Download {
             day: Day,
+            examples: bool,
+        },
+        Examples {
+            day: Day,
+            block: Option<usize>,
         },
         Read {
             day: Day,
@@ -27,17 +39,46 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            accept: bool,
+            timeout: Option<u64>,
+            visualization: Visualization,
         },
         All {
             release: bool,
+            isolated: bool,
+            timeout: Option<Duration>,
         },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            isolated: bool,
+            timeout: Option<Duration>,
+            baselines: BaselineOptions,
+            check: Option<CheckOptions>,
+        },
+        TimeHistory {
+            day: Day,
+        },
+        Stars {
+            offline: bool,
+            readme: bool,
         },
         #[cfg(feature = "today")]
-        Today,
+        Today {
+            wait: bool,
+            examples: bool,
+        },
+    }
+
+    /// Reads `--timeout <seconds>`, where `0` disables the timeout.
+    fn parse_timeout(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<Duration>, Box<dyn std::error::Error>> {
+        let secs = args
+            .opt_value_from_str("--timeout")?
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+        Ok((secs > 0).then(|| Duration::from_secs(secs)))
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
@@ -46,19 +87,56 @@ mod args {
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
+                isolated: args.contains("--isolated"),
+                timeout: parse_timeout(&mut args)?,
+            },
+            Some("time") if args.contains("--history") => AppArguments::TimeHistory {
+                day: args.free_from_str()?,
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let isolated = args.contains("--isolated");
+                let timeout = parse_timeout(&mut args)?;
+
+                // `--baseline <name>` implies `--compare`, which defaults to the stored baseline.
+                let compare = args.contains("--compare");
+                let baseline: Option<String> = args.opt_value_from_str("--baseline")?;
+                let baselines = BaselineOptions {
+                    compare: baseline.or_else(|| compare.then(|| STORED_BASELINE.to_string())),
+                    save: args.opt_value_from_str("--save-baseline")?,
+                };
+
+                // `--tolerance` and `--calibrate` imply `--check`.
+                let tolerance = args.opt_value_from_fn("--tolerance", parse_tolerance)?;
+                let calibrate = args.contains("--calibrate");
+                let is_check = args.contains("--check") || tolerance.is_some() || calibrate;
+                let check = is_check.then_some(CheckOptions {
+                    tolerance: tolerance.unwrap_or(CheckOptions::default().tolerance),
+                    calibrate,
+                });
 
                 AppArguments::Time {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    isolated,
+                    timeout,
+                    baselines,
+                    check,
                 }
             }
+            Some("stars") => AppArguments::Stars {
+                offline: args.contains("--offline"),
+                readme: args.contains("--readme"),
+            },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
+                examples: args.contains("--examples"),
+            },
+            Some("examples") => AppArguments::Examples {
+                day: args.free_from_str()?,
+                block: args.opt_value_from_str("--block")?,
             },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
@@ -73,9 +151,19 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                accept: args.contains("--accept"),
+                timeout: args.opt_value_from_str("--timeout")?,
+                visualization: Visualization {
+                    terminal: args.contains("--visualize"),
+                    render: args.contains("--render"),
+                    delay: args.opt_value_from_str("--delay")?,
+                },
             },
             #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
+            Some("today") => AppArguments::Today {
+                wait: args.contains("--wait"),
+                examples: args.contains("--examples"),
+            },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
                 process::exit(1);
@@ -95,6 +183,24 @@ mod args {
     }
 }
 
+/// Measures the peak heap usage of solutions that run in-process.
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+/// Solutions compiled into this binary, generated by `build.rs` from `src/bin/`.
+/// Only contains solutions with the `in-process` feature, which the `all` and `time` aliases enable.
+mod registry {
+    include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+}
+
+fn executor(isolated: bool, is_release: bool) -> Executor<'static> {
+    if isolated || cfg!(not(feature = "in-process")) {
+        Executor::Isolated { is_release }
+    } else {
+        Executor::InProcess(registry::SOLUTIONS)
+    }
+}
+
 fn main() {
     match parse() {
         Err(err) => {
@@ -102,9 +208,37 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
+            AppArguments::All {
+                release,
+                isolated,
+                timeout,
+            } => all::handle(executor(isolated, release), timeout),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                isolated,
+                timeout,
+                baselines,
+                check,
+            } => time::handle(
+                day,
+                all,
+                store,
+                timeout,
+                executor(isolated, true),
+                baselines,
+                check,
+            ),
+            AppArguments::TimeHistory { day } => time::history(day),
+            AppArguments::Stars { offline, readme } => stars::handle(offline, readme),
+            AppArguments::Download { day, examples } => {
+                download::handle(day);
+                if examples {
+                    examples::handle(day, None);
+                }
+            }
+            AppArguments::Examples { day, block } => examples::handle(day, block),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
                 day,
@@ -121,24 +255,12 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                accept,
+                timeout,
+                visualization,
+            } => solve::handle(day, release, dhat, submit, accept, timeout, visualization),
             #[cfg(feature = "today")]
-            AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
-                    }
-                    None => {
-                        eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
-                        );
-                        process::exit(1)
-                    }
-                };
-            }
+            AppArguments::Today { wait, examples } => today::handle(wait, examples),
         },
     };
 }
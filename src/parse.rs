@@ -0,0 +1,355 @@
+//! Helpers for parsing puzzle inputs that report where parsing failed.
+//!
+//! Wrap the input in a [`Span`] and narrow it down with [`Span::lines`], [`Span::sections`], [`Span::split`] or
+//! [`Span::pattern`]. Every span remembers its position within the input, so errors point to the offending
+//! line and column:
+//!
+//! ```text
+//! line 3, column 4: could not parse `x4` as u32: invalid digit found in string
+//!  3 | 47|x4
+//!    |    ^
+//! ```
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+/// Describes why and where parsing an input failed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the input.
+    pub line: usize,
+    /// 1-based column of the line, counted in characters.
+    pub column: usize,
+    pub message: String,
+    /// The text of the offending line.
+    pub context: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        writeln!(f, " {} | {}", self.line, self.context)?;
+        write!(f, " {gutter} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+// NOTE: `unwrap()` prints the `Debug` representation, so show the readable message there as well.
+impl Debug for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/* -------------------------------------------------------------------------- */
+
+/// A slice of the input that knows its position within the input.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    source: &'a str,
+    text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    /// Wraps a complete input.
+    pub fn new(input: &'a str) -> Self {
+        Span {
+            source: input,
+            text: input,
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Narrows the span to `text`, which must be a slice of this span.
+    fn narrow(&self, text: &'a str) -> Self {
+        Span {
+            source: self.source,
+            text,
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.text.as_ptr() as usize - self.source.as_ptr() as usize
+    }
+
+    /// Returns the 1-based line and column at which the span starts.
+    pub fn position(&self) -> (usize, usize) {
+        let before = &self.source[..self.offset()];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+
+    /// Creates an error that points to the start of the span.
+    pub fn error(&self, message: impl Display) -> ParseError {
+        let (line, column) = self.position();
+        let offset = self.offset();
+        let line_start = self.source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[offset..]
+            .find('\n')
+            .map_or(self.source.len(), |i| offset + i);
+
+        ParseError {
+            line,
+            column,
+            message: message.to_string(),
+            context: self.source[line_start..line_end].trim_end().to_string(),
+        }
+    }
+
+    pub fn trim(self) -> Self {
+        self.narrow(self.text.trim())
+    }
+
+    /// Iterates over the lines of the span.
+    pub fn lines(self) -> impl Iterator<Item = Span<'a>> {
+        self.text.lines().map(move |line| self.narrow(line))
+    }
+
+    /// Iterates over the blocks of the span that are separated by an empty line.
+    pub fn sections(self) -> impl Iterator<Item = Span<'a>> {
+        self.text
+            .trim_end()
+            .split("\n\n")
+            .map(move |section| self.narrow(section))
+    }
+
+    /// Iterates over the fields of the span that are separated by `delimiter`.
+    pub fn split(self, delimiter: &'a str) -> impl Iterator<Item = Span<'a>> {
+        self.text
+            .split(delimiter)
+            .map(move |field| self.narrow(field))
+    }
+
+    /// Iterates over the fields of the span that are separated by any amount of whitespace.
+    pub fn split_whitespace(self) -> impl Iterator<Item = Span<'a>> {
+        self.text
+            .split_whitespace()
+            .map(move |field| self.narrow(field))
+    }
+
+    /// Splits the span into exactly `N` fields that are separated by `delimiter`.
+    pub fn split_exact<const N: usize>(self, delimiter: &'a str) -> Result<[Span<'a>; N]> {
+        let fields: Vec<Span> = self.split(delimiter).collect();
+        fields.try_into().map_err(|fields: Vec<Span>| {
+            self.error(format!(
+                "expected {N} fields separated by {delimiter:?}, found {}.",
+                fields.len()
+            ))
+        })
+    }
+
+    /// Parses the span as a `T`.
+    pub fn parse<T>(self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.text.parse().map_err(|e| {
+            self.error(format!(
+                "could not parse `{}` as {}: {e}",
+                self.text,
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+
+    /// Parses all integers of the span, ignoring any text around them.
+    /// A `-` directly in front of an integer is treated as its sign, unless it follows a digit: `3-5` yields 3 and 5.
+    pub fn integers<T>(self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut integers = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            let is_negative =
+                i > 0 && bytes[i - 1] == b'-' && (i < 2 || !bytes[i - 2].is_ascii_digit());
+            let start = if is_negative { i - 1 } else { i };
+
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+
+            integers.push(self.narrow(&self.text[start..i]).parse()?);
+        }
+
+        Ok(integers)
+    }
+
+    /// Matches the span against a pattern such as `"{}|{}"` or `"p={},{}"`, returning the `N` spans that
+    /// take the place of the `{}` placeholders. A placeholder extends up to the first occurrence of the text
+    /// that follows it, the last placeholder up to the end of the span.
+    ///
+    /// # Panics
+    /// Panics if the pattern does not contain exactly `N` placeholders or has two adjacent placeholders.
+    pub fn pattern<const N: usize>(self, pattern: &str) -> Result<[Span<'a>; N]> {
+        let literals: Vec<&str> = pattern.split("{}").collect();
+        assert_eq!(
+            literals.len(),
+            N + 1,
+            "pattern {pattern:?} should have {N} placeholders."
+        );
+        assert!(
+            literals
+                .get(1..N)
+                .unwrap_or_default()
+                .iter()
+                .all(|literal| !literal.is_empty()),
+            "pattern {pattern:?} has adjacent placeholders."
+        );
+
+        let mut rest = self.expect_literal(literals[0])?;
+        let mut fields = Vec::with_capacity(N);
+
+        for literal in &literals[1..] {
+            let end = if literal.is_empty() {
+                rest.text.len()
+            } else {
+                rest.text.find(literal).ok_or_else(|| {
+                    rest.error(format!(
+                        "expected {literal:?} to follow, as in {pattern:?}."
+                    ))
+                })?
+            };
+
+            fields.push(rest.narrow(&rest.text[..end]));
+            rest = rest.narrow(&rest.text[end..]).expect_literal(literal)?;
+        }
+
+        if !rest.text.is_empty() {
+            return Err(rest.error(format!("unexpected {:?} after {pattern:?}.", rest.text)));
+        }
+
+        Ok(fields
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("the number of placeholders was checked above.")))
+    }
+
+    fn expect_literal(self, literal: &str) -> Result<Span<'a>> {
+        self.text
+            .strip_prefix(literal)
+            .map(|rest| self.narrow(rest))
+            .ok_or_else(|| self.error(format!("expected {literal:?}.")))
+    }
+}
+
+impl Display for Span<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Debug for Span<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (line, column) = self.position();
+        write!(f, "{:?} at {line}:{column}", self.text)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Span;
+
+    const INPUT: &str = "47|53\n97|13\n\n75,47,61\n97,x1,75\n";
+
+    #[test]
+    fn tracks_positions() {
+        let sections: Vec<Span> = Span::new(INPUT).sections().collect();
+        assert_eq!(sections.len(), 2);
+
+        let line = sections[1].lines().nth(1).unwrap();
+        assert_eq!(line.position(), (5, 1));
+
+        let field = line.split(",").nth(1).unwrap();
+        assert_eq!(field.position(), (5, 4));
+
+        let error = field.parse::<u32>().unwrap_err();
+        assert_eq!((error.line, error.column), (5, 4));
+        assert_eq!(error.context, "97,x1,75");
+        assert_eq!(
+            error.to_string(),
+            "line 5, column 4: could not parse `x1` as u32: invalid digit found in string\n 5 | 97,x1,75\n   |    ^"
+        );
+    }
+
+    #[test]
+    fn extracts_integers() {
+        let span = Span::new("p=-3,10 v=2-5 x-1");
+        assert_eq!(span.integers::<i32>().unwrap(), vec![-3, 10, 2, 5, -1]);
+
+        let error = Span::new("a 300").integers::<u8>().unwrap_err();
+        assert_eq!(error.column, 3);
+    }
+
+    #[test]
+    fn splits_exact_fields() {
+        let [left, right] = Span::new("12   34").split_exact("   ").unwrap();
+        assert_eq!((left.as_str(), right.as_str()), ("12", "34"));
+
+        let error = Span::new("1\n2   3   4")
+            .lines()
+            .nth(1)
+            .unwrap()
+            .split_exact::<2>("   ")
+            .unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            error.message,
+            "expected 2 fields separated by \"   \", found 3."
+        );
+    }
+
+    #[test]
+    fn splits_on_whitespace() {
+        let fields: Vec<Span> = Span::new("1  2 3 \r\n").split_whitespace().collect();
+        assert_eq!(
+            fields.iter().map(Span::as_str).collect::<Vec<_>>(),
+            ["1", "2", "3"]
+        );
+        assert_eq!(fields[1].position(), (1, 4));
+    }
+
+    #[test]
+    fn matches_patterns() {
+        let [x, y] = Span::new("p=3,-4").pattern("p={},{}").unwrap();
+        assert_eq!(x.parse::<i32>().unwrap(), 3);
+        assert_eq!(y.parse::<i32>().unwrap(), -4);
+        assert_eq!(y.position(), (1, 5));
+
+        let error = Span::new("47-53").pattern::<2>("{}|{}").unwrap_err();
+        assert_eq!(error.column, 1);
+        assert!(error.message.contains("\"|\""));
+
+        let error = Span::new("p=1,2!").pattern::<1>("p={},2").unwrap_err();
+        assert_eq!(error.column, 6);
+
+        // a pattern without placeholders only matches its text.
+        assert!(Span::new("-> ").pattern::<0>("-> ").is_ok());
+        assert!(Span::new("->").pattern::<0>("-> ").is_err());
+    }
+}
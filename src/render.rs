@@ -0,0 +1,336 @@
+//! Renders grids to PPM images and animated GIFs, to attach pictures of solutions to write-ups.
+//!
+//! Build an [`Image`] from a [`Grid`] and a palette that maps every cell to a color, paint overlays on top of it
+//! and save it with [`Image::save`], or collect images into an [`Animation`]. Files are only written when the
+//! solution runs with `--render`, e.g. `cargo solve <day> --render`, and end up in `data/renders/`.
+//! Other grid representations convert into a [`Grid`] first, e.g. `Grid::from(&array)` or `Grid::from(rows)`.
+use std::{
+    borrow::Cow, collections::HashMap, env, fmt::Display, fs, io, path::PathBuf, sync::OnceLock,
+};
+
+use crate::{
+    geom::Point,
+    grid::Grid,
+    template::Day,
+    visualize::{Color, RENDER_OPTION},
+};
+
+/// Width and height in pixels that a cell is drawn with.
+const CELL_SIZE: usize = 4;
+
+/// A color as red, green and blue components.
+pub type Rgb = [u8; 3];
+
+impl From<Color> for Rgb {
+    fn from(value: Color) -> Self {
+        match value {
+            Color::Red => [220, 50, 47],
+            Color::Green => [133, 153, 0],
+            Color::Yellow => [255, 215, 0],
+            Color::Blue => [38, 139, 210],
+            Color::Magenta => [211, 54, 130],
+            Color::Cyan => [42, 161, 152],
+            Color::White => [238, 232, 213],
+            Color::Gray => [88, 110, 117],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    /// GIFs can only use up to 256 distinct colors.
+    TooManyColors(usize),
+    /// The frames of an animation differ in size.
+    FrameSize,
+    Encoding(gif::EncodingError),
+    Io(io::Error),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::TooManyColors(count) => {
+                write!(f, "animation uses {count} colors, GIFs support up to 256.")
+            }
+            RenderError::FrameSize => write!(f, "frames of the animation differ in size."),
+            RenderError::Encoding(e) => write!(f, "could not encode GIF: {e}"),
+            RenderError::Io(e) => write!(f, "could not write image: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<io::Error> for RenderError {
+    fn from(value: io::Error) -> Self {
+        RenderError::Io(value)
+    }
+}
+
+impl From<gif::EncodingError> for RenderError {
+    fn from(value: gif::EncodingError) -> Self {
+        RenderError::Encoding(value)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A picture of a grid with one color per cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pixels: Grid<Rgb>,
+}
+
+impl Image {
+    /// Paints every cell of `grid` with the color returned by `palette`.
+    pub fn new<T>(grid: &Grid<T>, mut palette: impl FnMut(&T) -> Rgb) -> Self {
+        Image {
+            pixels: grid.map(|cell| palette(cell)),
+        }
+    }
+
+    /// Paints the cells at `points`. Points outside of the image are skipped.
+    pub fn paint<P: Into<Point>>(
+        mut self,
+        points: impl IntoIterator<Item = P>,
+        color: impl Into<Rgb>,
+    ) -> Self {
+        let color = color.into();
+        for point in points {
+            if let Some(pixel) = self.pixels.get_mut(point) {
+                *pixel = color;
+            }
+        }
+        self
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (
+            self.pixels.width() * CELL_SIZE,
+            self.pixels.height() * CELL_SIZE,
+        )
+    }
+
+    /// Iterates over the colors of the scaled image, row by row.
+    fn scaled(&self) -> impl Iterator<Item = Rgb> + '_ {
+        self.pixels.rows().flat_map(|row| {
+            std::iter::repeat_n(row, CELL_SIZE).flat_map(|row| {
+                row.iter()
+                    .flat_map(|&rgb| std::iter::repeat_n(rgb, CELL_SIZE))
+            })
+        })
+    }
+
+    /// Encodes the image as a binary PPM (P6).
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let (width, height) = self.size();
+        let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+        ppm.extend(self.scaled().flatten());
+        ppm
+    }
+
+    /// Writes the image to `data/renders/<day>-<name>.ppm` if rendering is enabled.
+    pub fn save(&self, day: Day, name: &str) {
+        write_render(day, name, "ppm", || Ok(self.to_ppm()));
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A sequence of images that is played back as an animated GIF.
+#[derive(Clone, Debug, Default)]
+pub struct Animation {
+    frames: Vec<Image>,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, frame: Image) {
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Encodes the animation as a GIF that loops forever, showing every frame for `delay_ms` milliseconds.
+    pub fn to_gif(&self, delay_ms: u64) -> Result<Vec<u8>, RenderError> {
+        let Some(first) = self.frames.first() else {
+            return Ok(Vec::new());
+        };
+        let (width, height) = first.size();
+        if self
+            .frames
+            .iter()
+            .any(|frame| frame.size() != (width, height))
+        {
+            return Err(RenderError::FrameSize);
+        }
+
+        // every frame refers to a shared palette, built from the colors in order of appearance.
+        let mut indices: HashMap<Rgb, u8> = HashMap::new();
+        let mut palette: Vec<u8> = Vec::new();
+        for &rgb in self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.pixels.iter().map(|(_, rgb)| rgb))
+        {
+            if !indices.contains_key(&rgb) {
+                let index = u8::try_from(indices.len())
+                    .map_err(|_| RenderError::TooManyColors(indices.len() + 1))?;
+                indices.insert(rgb, index);
+                palette.extend(rgb);
+            }
+        }
+
+        let dimension = |size: usize| u16::try_from(size).map_err(|_| RenderError::FrameSize);
+        let (width, height) = (dimension(width)?, dimension(height)?);
+        let delay = u16::try_from(delay_ms / 10).unwrap_or(u16::MAX);
+
+        let mut gif = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut gif, width, height, &palette)?;
+            encoder.set_repeat(gif::Repeat::Infinite)?;
+
+            for image in &self.frames {
+                let mut frame = gif::Frame {
+                    width,
+                    height,
+                    delay,
+                    ..gif::Frame::default()
+                };
+                frame.buffer = Cow::Owned(image.scaled().map(|rgb| indices[&rgb]).collect());
+                encoder.write_frame(&frame)?;
+            }
+        }
+        Ok(gif)
+    }
+
+    /// Writes the animation to `data/renders/<day>-<name>.gif` if rendering is enabled.
+    /// The frames are shown for the delay set with `--delay`.
+    pub fn save(&self, day: Day, name: &str) {
+        let delay = crate::visualize::delay();
+        write_render(day, name, "gif", || {
+            self.to_gif(delay.as_millis().try_into().unwrap_or(u64::MAX))
+        });
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Whether the solution was run with `--render`.
+pub fn is_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| env::args().any(|x| x == RENDER_OPTION))
+}
+
+/// Encodes and writes a render, reporting the outcome on stderr.
+/// NOTE: a failing render should not end the solution, so errors are only reported.
+fn write_render(
+    day: Day,
+    name: &str,
+    extension: &str,
+    encode: impl FnOnce() -> Result<Vec<u8>, RenderError>,
+) {
+    if !is_enabled() {
+        return;
+    }
+
+    let dir = PathBuf::from("data").join("renders");
+    let path = dir.join(format!("{day}-{name}.{extension}"));
+    let result = encode().and_then(|bytes| {
+        fs::create_dir_all(&dir)?;
+        fs::write(&path, bytes)?;
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => eprintln!("Rendered {}.", path.display()),
+        Err(e) => eprintln!("Could not render {}: {e}", path.display()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Animation, Image, RenderError, Rgb};
+    use crate::{grid::Grid, visualize::Color};
+
+    const BLACK: Rgb = [0, 0, 0];
+    const WHITE: Rgb = [255, 255, 255];
+
+    fn image() -> Image {
+        let grid = Grid::parse("#.\n..\n", |c| c == '#');
+        Image::new(&grid, |&wall| if wall { WHITE } else { BLACK })
+    }
+
+    #[test]
+    fn encodes_ppm() {
+        let ppm = image().paint([(1, 1)], Color::Red).to_ppm();
+        let header = b"P6\n8 8\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 8 * 8 * 3);
+
+        let pixels = &ppm[header.len()..];
+        let pixel = |x: usize, y: usize| &pixels[(y * 8 + x) * 3..(y * 8 + x + 1) * 3];
+        assert_eq!(pixel(3, 3), WHITE);
+        assert_eq!(pixel(4, 3), BLACK);
+        assert_eq!(pixel(7, 7), Rgb::from(Color::Red));
+    }
+
+    #[test]
+    fn encodes_gifs() {
+        let mut animation = Animation::new();
+        animation.push(image());
+        animation.push(image().paint([(0, 1)], Color::Blue));
+
+        let gif = animation.to_gif(100).unwrap();
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(gif.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (8, 8));
+
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 10);
+            frames.push(frame.buffer.to_vec());
+        }
+        assert_eq!(frames.len(), 2);
+        // bottom left cell of the second frame, 4 bytes per pixel.
+        assert_eq!(
+            &frames[1][(7 * 8) * 4..(7 * 8) * 4 + 3],
+            Rgb::from(Color::Blue)
+        );
+        assert_eq!(&frames[0][(7 * 8) * 4..(7 * 8) * 4 + 3], BLACK);
+
+        let grid = Grid::new(3, 1, ());
+        let mut animation = Animation::new();
+        animation.push(image());
+        animation.push(Image::new(&grid, |_| WHITE));
+        assert!(matches!(animation.to_gif(100), Err(RenderError::FrameSize)));
+    }
+
+    #[test]
+    fn limits_gif_colors() {
+        let grid = Grid::parse(&".".repeat(300), |c| c);
+        let mut x = 0;
+        let image = Image::new(&grid, |_| {
+            x += 1;
+            [(x % 256) as u8, (x / 256) as u8, 0]
+        });
+        let mut animation = Animation::new();
+        animation.push(image);
+        assert!(matches!(
+            animation.to_gif(100),
+            Err(RenderError::TooManyColors(257))
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+//! Stores accepted answers so that later runs can be verified against them.
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::Day;
+
+static ANSWERS_FILE_PATH: &str = "./data/answers.json";
+
+/// Represents the accepted answer for a single part of a day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub day: Day,
+    pub part: u8,
+    pub answer: String,
+}
+
+/// Represents all accepted answers.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    pub data: Vec<Answer>,
+}
+
+/// The result of comparing an answer against the accepted one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "✔"),
+            Verdict::Wrong { .. } => write!(f, "✖"),
+            Verdict::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+impl Answers {
+    /// Dehydrate answers to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(ANSWERS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate answers from a JSON file. If not present, returns no answers.
+    /// Fails if the file cannot be read or parsed, so that it is not overwritten by the next `accept`.
+    pub fn read_from_file() -> Result<Self, String> {
+        match fs::read_to_string(ANSWERS_FILE_PATH) {
+            Ok(contents) => Answers::try_from(contents)
+                .map_err(|e| format!("\"{ANSWERS_FILE_PATH}\" is corrupt, {e}")),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("could not read \"{ANSWERS_FILE_PATH}\": {e}")),
+        }
+    }
+
+    /// Rehydrate answers for checking results. If the file cannot be read, prints a warning and returns no answers.
+    pub fn read_or_warn() -> Self {
+        Answers::read_from_file().unwrap_or_else(|e| {
+            eprintln!("Warning: answers are not verified, {e}");
+            Answers::default()
+        })
+    }
+
+    /// Returns the accepted answer for a part, if any.
+    pub fn get(&self, day: Day, part: u8) -> Option<&str> {
+        self.data
+            .iter()
+            .find(|a| a.day == day && a.part == part)
+            .map(|a| a.answer.as_str())
+    }
+
+    /// Records `answer` as accepted, replacing a previously accepted answer for the same part.
+    pub fn accept(&mut self, day: Day, part: u8, answer: &str) {
+        self.data.retain(|a| !(a.day == day && a.part == part));
+        self.data.push(Answer {
+            day,
+            part,
+            answer: answer.into(),
+        });
+        self.data.sort_unstable_by_key(|a| (a.day, a.part));
+    }
+
+    /// Compares `answer` with the accepted answer for a part.
+    /// A missing answer is wrong if an answer has been accepted before.
+    pub fn check(&self, day: Day, part: u8, answer: Option<&str>) -> Verdict {
+        match (self.get(day, part), answer) {
+            (Some(expected), Some(answer)) if expected == answer => Verdict::Correct,
+            (Some(expected), _) => Verdict::Wrong {
+                expected: expected.into(),
+            },
+            (None, _) => Verdict::Unknown,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Answers> for JsonValue {
+    fn from(value: Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(Answer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Answer> for JsonValue {
+    fn from(value: &Answer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("answer".into(), JsonValue::String(value.answer.clone()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Answer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected answer.day to be a Day struct.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .filter(|&&part| part == 1.0 || part == 2.0)
+            .ok_or("Expected answer.part to be 1 or 2.")?;
+
+        let answer = json
+            .get("answer")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected answer.answer to be a string.")?;
+
+        Ok(Answer {
+            day,
+            part: if *part == 1.0 { 1 } else { 2 },
+            answer: answer.clone(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Answers, Verdict};
+    use crate::day;
+
+    #[test]
+    fn checks_answers() {
+        let mut answers = Answers::default();
+        answers.accept(day!(5), 1, "143");
+
+        assert_eq!(answers.check(day!(5), 1, Some("143")), Verdict::Correct);
+        assert_eq!(
+            answers.check(day!(5), 1, Some("144")),
+            Verdict::Wrong {
+                expected: "143".into()
+            }
+        );
+        assert_eq!(
+            answers.check(day!(5), 1, None),
+            Verdict::Wrong {
+                expected: "143".into()
+            }
+        );
+        assert_eq!(answers.check(day!(5), 2, Some("123")), Verdict::Unknown);
+    }
+
+    #[test]
+    fn replaces_accepted_answers() {
+        let mut answers = Answers::default();
+        answers.accept(day!(5), 2, "1");
+        answers.accept(day!(1), 1, "2");
+        answers.accept(day!(5), 2, "3");
+
+        assert_eq!(answers.data.len(), 2);
+        assert_eq!(answers.data[0].day, day!(1));
+        assert_eq!(answers.get(day!(5), 2), Some("3"));
+    }
+
+    #[test]
+    fn roundtrips_json() {
+        let mut answers = Answers::default();
+        answers.accept(day!(6), 1, "41");
+        answers.accept(day!(6), 2, "multi\nline");
+
+        let json = tinyjson::JsonValue::from(answers.clone())
+            .stringify()
+            .unwrap();
+        let parsed = Answers::try_from(json).unwrap();
+        assert_eq!(parsed.data, answers.data);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_parts() {
+        let json = r#"{ "data": [{ "day": "01", "part": 3, "answer": "1" }] }"#.to_string();
+        Answers::try_from(json).unwrap();
+    }
+}
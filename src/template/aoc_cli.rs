@@ -4,7 +4,7 @@ use std::{
     process::{Command, Output, Stdio},
 };
 
-use crate::template::Day;
+use crate::template::{submission::Response, Day};
 
 #[derive(Debug)]
 pub enum AocCommandError {
@@ -72,12 +72,28 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
     Ok(output)
 }
 
-pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
+pub fn submit(day: Day, part: u8, result: &str) -> Result<Response, AocCommandError> {
     // workaround: the argument order is inverted for submit.
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+
+    // capture stdout so that the response can be parsed, then forward it.
+    let output = Command::new("aoc")
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|_| AocCommandError::CommandNotCallable)?;
+
+    let message = String::from_utf8_lossy(&output.stdout);
+    print!("{message}");
+
+    if output.status.success() {
+        Ok(Response::parse(&message))
+    } else {
+        Err(AocCommandError::BadExitStatus(output))
+    }
 }
 
 fn get_input_path(day: Day) -> String {
@@ -0,0 +1,338 @@
+//! A client for the Advent of Code website, used to download inputs and puzzles and to submit answers.
+//!
+//! The session cookie is read from the `AOC_SESSION` environment variable or from `~/.adventofcode.session`.
+//! Requests go to `AOC_BASE_URL`, which defaults to the Advent of Code website. Set `AOC_CLIENT=aoc-cli` to use the
+//! `aoc` command-line instead, see [`crate::template::aoc_cli`].
+use std::{env, fmt::Display, fs, io, path::PathBuf, time::Duration};
+
+use crate::template::{markdown, submission::Response, Day};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const SESSION_FILE_NAME: &str = ".adventofcode.session";
+const USER_AGENT: &str = concat!(
+    "github.com/fspoettel/advent-of-code-rust ",
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION")
+);
+
+#[derive(Debug)]
+pub enum AocClientError {
+    SessionNotFound,
+    MissingYear,
+    Http { status: u16, url: String },
+    Transport(String),
+    Io(io::Error),
+}
+
+impl Display for AocClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocClientError::SessionNotFound => write!(
+                f,
+                "no session cookie found. Set AOC_SESSION or write it to \"~/{SESSION_FILE_NAME}\"."
+            ),
+            AocClientError::MissingYear => write!(f, "AOC_YEAR is not set to a valid year."),
+            AocClientError::Http { status, url } => {
+                write!(f, "request to {url} failed with status {status}.")
+            }
+            AocClientError::Transport(e) => write!(f, "request failed: {e}"),
+            AocClientError::Io(e) => write!(f, "could not write file: {e}"),
+        }
+    }
+}
+
+impl AocClientError {
+    /// Whether retrying the request may succeed, e.g. if a puzzle did not unlock yet.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            AocClientError::Http { status, .. } => *status == 404 || *status >= 500,
+            AocClientError::Transport(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<io::Error> for AocClientError {
+    fn from(value: io::Error) -> Self {
+        AocClientError::Io(value)
+    }
+}
+
+/// Whether `AOC_CLIENT` selects the `aoc` command-line instead of the built-in client.
+pub fn uses_aoc_cli() -> bool {
+    env::var("AOC_CLIENT").is_ok_and(|client| client == "aoc-cli")
+}
+
+pub struct AocClient {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    year: u16,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str, session: &str, year: u16) -> Self {
+        AocClient {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            year,
+        }
+    }
+
+    /// Configures a client from the environment, see the module documentation.
+    pub fn from_env() -> Result<Self, AocClientError> {
+        let year = env::var("AOC_YEAR")
+            .ok()
+            .and_then(|year| year.parse().ok())
+            .ok_or(AocClientError::MissingYear)?;
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        Ok(Self::new(&base_url, &read_session()?, year))
+    }
+
+    /// Downloads the puzzle input of `day`.
+    pub fn input(&self, day: Day) -> Result<String, AocClientError> {
+        self.get(&format!("/day/{}/input", day.into_inner()))
+    }
+
+    /// Downloads the puzzle page of `day`.
+    pub fn puzzle_html(&self, day: Day) -> Result<String, AocClientError> {
+        self.get(&format!("/day/{}", day.into_inner()))
+    }
+
+    /// Downloads the puzzle description of `day` and converts it to Markdown.
+    pub fn puzzle_markdown(&self, day: Day) -> Result<String, AocClientError> {
+        self.puzzle_html(day)
+            .map(|html| markdown::from_puzzle_html(&html))
+    }
+
+    /// Downloads the private leaderboard with `id` as JSON.
+    /// NOTE: the website asks to not request a leaderboard more often than every 15 minutes.
+    pub fn leaderboard_json(&self, id: u64) -> Result<String, AocClientError> {
+        self.get(&format!("/leaderboard/private/view/{id}.json"))
+    }
+
+    /// Submits `answer` for a part and returns the response, with its message as Markdown.
+    pub fn submit(&self, day: Day, part: u8, answer: &str) -> Result<Response, AocClientError> {
+        let url = self.url(&format!("/day/{}/answer", day.into_inner()));
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+        read_response(response, &url)
+            .map(|html| Response::parse(&markdown::from_puzzle_html(&html)))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}{path}", self.base_url, self.year)
+    }
+
+    fn get(&self, path: &str) -> Result<String, AocClientError> {
+        let url = self.url(path);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+        read_response(response, &url)
+    }
+}
+
+fn read_response(
+    response: Result<ureq::Response, ureq::Error>,
+    url: &str,
+) -> Result<String, AocClientError> {
+    match response {
+        Ok(response) => response.into_string().map_err(AocClientError::Io),
+        Err(ureq::Error::Status(status, _)) => Err(AocClientError::Http {
+            status,
+            url: url.to_string(),
+        }),
+        Err(ureq::Error::Transport(e)) => Err(AocClientError::Transport(e.to_string())),
+    }
+}
+
+/// Reads the session cookie from `AOC_SESSION`, or from the session file in the home directory.
+fn read_session() -> Result<String, AocClientError> {
+    if let Some(session) = env::var("AOC_SESSION")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+    {
+        return Ok(session);
+    }
+
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(SESSION_FILE_NAME))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .filter(|session| !session.trim().is_empty())
+        .ok_or(AocClientError::SessionNotFound)
+}
+
+fn get_input_path(day: Day) -> String {
+    format!("data/inputs/{day}.txt")
+}
+
+fn get_puzzle_path(day: Day) -> String {
+    format!("data/puzzles/{day}.md")
+}
+
+/// Downloads the input and puzzle description of `day` to `data/`.
+pub fn download(day: Day) -> Result<(), AocClientError> {
+    let client = AocClient::from_env()?;
+    let input_path = get_input_path(day);
+    let puzzle_path = get_puzzle_path(day);
+
+    fs::write(&input_path, client.input(day)?)?;
+    fs::write(&puzzle_path, client.puzzle_markdown(day)?)?;
+
+    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
+    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    Ok(())
+}
+
+/// Downloads the puzzle description of `day` to `data/puzzles/` and prints it.
+pub fn read(day: Day) -> Result<(), AocClientError> {
+    let client = AocClient::from_env()?;
+    let puzzle = client.puzzle_markdown(day)?;
+    fs::write(get_puzzle_path(day), &puzzle)?;
+    print!("{puzzle}");
+    Ok(())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use super::{AocClient, AocClientError};
+    use crate::{
+        day,
+        template::submission::{Outcome, Response},
+    };
+
+    /// Serves one canned response per request and returns the requests it received.
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:")
+                    {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                requests.push(request);
+
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            requests
+        });
+
+        (base_url, handle)
+    }
+
+    #[test]
+    fn downloads_from_the_website() {
+        let (base_url, server) = serve(vec![
+            (200, "1 2\n3 4\n"),
+            (
+                200,
+                "<main><article><h2>--- Day 6: Guard Gallivant ---</h2><p>Part one.</p></article></main>",
+            ),
+            (404, "Not Found"),
+            (200, "{}"),
+        ]);
+        let client = AocClient::new(&format!("{base_url}/"), "abc123\n", 2024);
+
+        assert_eq!(client.input(day!(6)).unwrap(), "1 2\n3 4\n");
+        assert_eq!(
+            client.puzzle_markdown(day!(6)).unwrap(),
+            "## --- Day 6: Guard Gallivant ---\n\nPart one.\n"
+        );
+        let error = client.input(day!(25)).unwrap_err();
+        assert!(error.is_transient());
+        match error {
+            AocClientError::Http { status, url } => {
+                assert_eq!(status, 404);
+                assert_eq!(url, format!("{base_url}/2024/day/25/input"));
+            }
+            error => panic!("expected an HTTP error, got {error:?}"),
+        }
+        assert_eq!(client.leaderboard_json(3031).unwrap(), "{}");
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2024/day/6/input HTTP/1.1\r\n"));
+        assert!(requests[1].starts_with("GET /2024/day/6 HTTP/1.1\r\n"));
+        assert!(
+            requests[3].starts_with("GET /2024/leaderboard/private/view/3031.json HTTP/1.1\r\n")
+        );
+        for request in &requests {
+            let request = request.to_ascii_lowercase();
+            assert!(
+                request.contains("\r\ncookie: session=abc123\r\n"),
+                "{request}"
+            );
+            assert!(request.contains("\r\nuser-agent: github.com/fspoettel/advent-of-code-rust"));
+        }
+    }
+
+    #[test]
+    fn submits_answers() {
+        let (base_url, server) = serve(vec![(
+            200,
+            "<main><article><p>That's the right answer! You are <em>one gold star</em> closer.</p></article></main>",
+        )]);
+        let client = AocClient::new(&base_url, "abc123", 2024);
+
+        assert_eq!(
+            client.submit(day!(6), 2, "1382").unwrap(),
+            Response {
+                outcome: Outcome::Correct,
+                wait: None,
+                message: "That's the right answer! You are *one gold star* closer.\n".into()
+            }
+        );
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2024/day/6/answer HTTP/1.1\r\n"));
+        assert!(
+            requests[0].ends_with("\r\n\r\nlevel=2&answer=1382"),
+            "{}",
+            requests[0]
+        );
+    }
+}
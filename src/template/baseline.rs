@@ -0,0 +1,363 @@
+//! Named sets of benchmark statistics that new timings can be compared against.
+//! Baselines are stored in `data/baselines/<name>.json` and written with `cargo time --save-baseline <name>`.
+//! The baseline `stored` is not a file, it is made of the timings that `cargo time --store` keeps in
+//! `data/timings.json`.
+use std::{collections::HashMap, fmt::Display, fs, io, path::PathBuf, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{
+    protocol::{PartRecord, Status, PARSE_PART},
+    timings::Timings,
+    Day,
+};
+
+static BASELINES_DIR: &str = "./data/baselines";
+
+/// Baseline of the stored timings, which `cargo time --compare` uses by default.
+pub const STORED_BASELINE: &str = "stored";
+
+/// A difference between two medians is only significant if it exceeds this many standard errors.
+/// 1.96 corresponds to a confidence of 95% for normally distributed samples.
+const SIGNIFICANCE_Z: f64 = 1.96;
+
+/// Differences below this fraction of the baseline are considered noise, no matter how many samples were taken.
+const MIN_RELATIVE_CHANGE: f64 = 0.02;
+
+/// Benchmark statistics of a part, or of the parse phase if `part` is [`PARSE_PART`](super::protocol::PARSE_PART).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measurement {
+    pub day: Day,
+    pub part: u8,
+    pub median_nanos: f64,
+    pub std_dev_nanos: f64,
+    /// Number of samples that the statistics are based on, not counting outliers.
+    pub samples: u128,
+}
+
+impl Measurement {
+    /// Takes the statistics of a solved part. Returns `None` for any other record.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_record(day: Day, record: &PartRecord) -> Option<Self> {
+        (record.status == Status::Solved).then(|| Measurement {
+            day,
+            part: record.part,
+            median_nanos: record.stats.median.as_nanos() as f64,
+            std_dev_nanos: record.stats.std_dev.as_nanos() as f64,
+            samples: record.stats.samples - record.stats.outliers,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Baseline {
+    pub measurements: Vec<Measurement>,
+}
+
+impl Baseline {
+    fn path(name: &str) -> PathBuf {
+        PathBuf::from(BASELINES_DIR).join(format!("{name}.json"))
+    }
+
+    /// Reads the baseline `name`. Returns `None` if it does not exist.
+    pub fn read(name: &str) -> Result<Option<Self>, String> {
+        match fs::read_to_string(Self::path(name)) {
+            Ok(json) => Baseline::try_from(json)
+                .map(Some)
+                .map_err(|e| format!("baseline `{name}` is invalid: {e}")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("could not read baseline `{name}`: {e}")),
+        }
+    }
+
+    pub fn store(&self, name: &str) -> Result<(), io::Error> {
+        fs::create_dir_all(BASELINES_DIR)?;
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(Self::path(name))?;
+        json.format_to(&mut file)
+    }
+
+    /// Merge two baselines, overwriting measurements of `self` with those of `new`.
+    pub fn merge(&self, new: &Self) -> Self {
+        let mut measurements = new.measurements.clone();
+
+        for measurement in &self.measurements {
+            if new.get(measurement.day, measurement.part).is_none() {
+                measurements.push(*measurement);
+            }
+        }
+
+        measurements.sort_unstable_by_key(|m| (m.day, m.part));
+        Baseline { measurements }
+    }
+
+    pub fn get(&self, day: Day, part: u8) -> Option<&Measurement> {
+        self.measurements
+            .iter()
+            .find(|m| m.day == day && m.part == part)
+    }
+}
+
+/// The statistics of the stored timings, see [`STORED_BASELINE`].
+impl From<&Timings> for Baseline {
+    fn from(value: &Timings) -> Self {
+        let measurements = value
+            .data
+            .iter()
+            .flat_map(|timing| {
+                [PARSE_PART, 1, 2].into_iter().filter_map(|part| {
+                    timing.part(part).map(|stored| Measurement {
+                        day: timing.day,
+                        part,
+                        median_nanos: stored.median_nanos,
+                        std_dev_nanos: stored.std_dev_nanos,
+                        samples: stored.samples,
+                    })
+                })
+            })
+            .collect();
+
+        Baseline { measurements }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// How a new measurement relates to its baseline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Faster,
+    Slower,
+    /// The difference is within the noise of the measurements.
+    Unchanged,
+    /// There is no baseline, or too few samples to judge the difference.
+    Unknown,
+}
+
+impl Change {
+    /// Compares the medians of two measurements with a z-test, using the standard error of both.
+    pub fn between(old: &Measurement, new: &Measurement) -> Self {
+        let delta = new.median_nanos - old.median_nanos;
+
+        if old.median_nanos > 0.0 && (delta / old.median_nanos).abs() < MIN_RELATIVE_CHANGE {
+            return Change::Unchanged;
+        }
+
+        if old.samples < 2 || new.samples < 2 {
+            return Change::Unknown;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let standard_error = (old.std_dev_nanos.powi(2) / old.samples as f64
+            + new.std_dev_nanos.powi(2) / new.samples as f64)
+            .sqrt();
+
+        if delta.abs() <= SIGNIFICANCE_Z * standard_error {
+            Change::Unchanged
+        } else if delta < 0.0 {
+            Change::Faster
+        } else {
+            Change::Slower
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Faster => write!(f, "✔ faster"),
+            Change::Slower => write!(f, "✖ slower"),
+            Change::Unchanged => write!(f, "≈ unchanged"),
+            Change::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Baseline> for JsonValue {
+    fn from(value: Baseline) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.measurements.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Baseline {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Baseline {
+            measurements: json_data
+                .iter()
+                .map(Measurement::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<&Measurement> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Measurement) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("median_nanos".into(), JsonValue::Number(value.median_nanos));
+        map.insert(
+            "std_dev_nanos".into(),
+            JsonValue::Number(value.std_dev_nanos),
+        );
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Measurement {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected measurement to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected measurement.{key} to be a number."))
+        };
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected measurement.day to be a Day struct.")?;
+
+        Ok(Measurement {
+            day,
+            part: number("part")? as u8,
+            median_nanos: number("median_nanos")?,
+            std_dev_nanos: number("std_dev_nanos")?,
+            samples: number("samples")? as u128,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Baseline, Change, Measurement};
+    use crate::{
+        day,
+        template::timings::{PartTiming, Timing, Timings},
+    };
+
+    fn measurement(part: u8, median_nanos: f64, std_dev_nanos: f64, samples: u128) -> Measurement {
+        Measurement {
+            day: day!(1),
+            part,
+            median_nanos,
+            std_dev_nanos,
+            samples,
+        }
+    }
+
+    #[test]
+    fn detects_significant_changes() {
+        let old = measurement(1, 1000.0, 50.0, 1000);
+        assert_eq!(
+            Change::between(&old, &measurement(1, 900.0, 50.0, 1000)),
+            Change::Faster
+        );
+        assert_eq!(
+            Change::between(&old, &measurement(1, 1100.0, 50.0, 1000)),
+            Change::Slower
+        );
+        // within 2% of the baseline.
+        assert_eq!(
+            Change::between(&old, &measurement(1, 1010.0, 1.0, 1000)),
+            Change::Unchanged
+        );
+        // large, but noisy difference.
+        assert_eq!(
+            Change::between(
+                &measurement(1, 1000.0, 500.0, 10),
+                &measurement(1, 1200.0, 500.0, 10)
+            ),
+            Change::Unchanged
+        );
+        assert_eq!(
+            Change::between(&old, &measurement(1, 2000.0, 0.0, 1)),
+            Change::Unknown
+        );
+    }
+
+    #[test]
+    fn merges_and_serializes_baselines() {
+        let old = Baseline {
+            measurements: vec![measurement(1, 10.0, 1.0, 10), measurement(2, 20.0, 2.0, 10)],
+        };
+        let new = Baseline {
+            measurements: vec![measurement(2, 15.0, 1.0, 100)],
+        };
+
+        let merged = old.merge(&new);
+        assert_eq!(merged.measurements.len(), 2);
+        assert_eq!(merged.get(day!(1), 2).unwrap().median_nanos, 15.0);
+
+        let json = tinyjson::JsonValue::from(merged.clone())
+            .stringify()
+            .unwrap();
+        assert_eq!(Baseline::try_from(json).unwrap(), merged);
+        assert!(Baseline::try_from(r#"{ "data": [{ "day": "01" }] }"#.to_string()).is_err());
+    }
+
+    #[test]
+    fn reads_stored_timings() {
+        let part = |median_nanos: f64| {
+            Some(PartTiming {
+                median_nanos,
+                std_dev_nanos: 1.0,
+                samples: 10,
+                memory_peak_bytes: None,
+            })
+        };
+        let timings = Timings {
+            data: vec![Timing {
+                day: day!(1),
+                parse: part(5.0),
+                part_1: None,
+                part_2: part(20.0),
+                calibration_nanos: None,
+            }],
+        };
+
+        let baseline = Baseline::from(&timings);
+        assert_eq!(baseline.measurements.len(), 2);
+        assert_eq!(
+            baseline.get(day!(1), 0),
+            Some(&measurement(0, 5.0, 1.0, 10))
+        );
+        assert_eq!(
+            baseline.get(day!(1), 2),
+            Some(&measurement(2, 20.0, 1.0, 10))
+        );
+    }
+}
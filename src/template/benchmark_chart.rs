@@ -0,0 +1,146 @@
+//! Draws stored timings as an SVG bar chart, which the rich benchmark table in the readme links to.
+//! Every day is a bar, split into its parse phase and both parts.
+use std::fmt::Write;
+
+use crate::template::timings::{PartTiming, Timings};
+
+/// Where the chart is written to, relative to the repository root.
+pub const CHART_PATH: &str = ".assets/benchmarks.svg";
+
+const WIDTH: f64 = 640.0;
+const ROW_HEIGHT: f64 = 24.0;
+const BAR_HEIGHT: f64 = 16.0;
+/// Space above the bars for the legend.
+const HEADER_HEIGHT: f64 = 32.0;
+const LABEL_WIDTH: f64 = 64.0;
+/// Space right of the longest bar for its total.
+const TOTAL_WIDTH: f64 = 88.0;
+
+/// Colors of the parse phase and both parts.
+const SEGMENTS: [(&str, &str); 3] = [
+    ("Parse", "#88c0d0"),
+    ("Part 1", "#5e81ac"),
+    ("Part 2", "#bf616a"),
+];
+
+/// Renders one horizontal bar per day, scaled to the slowest day.
+pub fn render(timings: &Timings) -> String {
+    let max_nanos = timings
+        .data
+        .iter()
+        .map(|timing| timing.total_nanos())
+        .fold(0.0, f64::max);
+    let bar_width = WIDTH - LABEL_WIDTH - TOTAL_WIDTH;
+    #[allow(clippy::cast_precision_loss)]
+    let height = HEADER_HEIGHT + ROW_HEIGHT * timings.data.len() as f64;
+
+    let mut svg = String::new();
+    // NOTE: writing to a string cannot fail.
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" font-family="monospace" font-size="12">"#
+    );
+
+    let mut x = LABEL_WIDTH;
+    for (label, color) in SEGMENTS {
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{x}" y="8" width="12" height="12" fill="{color}"/><text x="{}" y="18" fill="#888">{label}</text>"##,
+            x + 16.0
+        );
+        x += 88.0;
+    }
+
+    for (row, timing) in timings.data.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let y = HEADER_HEIGHT + ROW_HEIGHT * row as f64;
+        let text_y = y + BAR_HEIGHT - 4.0;
+        let _ = writeln!(
+            svg,
+            r##"  <text x="0" y="{text_y}" fill="#888">Day {}</text>"##,
+            timing.day
+        );
+
+        let mut x = LABEL_WIDTH;
+        let parts: [&Option<PartTiming>; 3] = [&timing.parse, &timing.part_1, &timing.part_2];
+        for (part, (label, color)) in parts.into_iter().zip(SEGMENTS) {
+            let Some(part) = part else {
+                continue;
+            };
+            let width = if max_nanos > 0.0 {
+                part.median_nanos / max_nanos * bar_width
+            } else {
+                0.0
+            };
+            let _ = writeln!(
+                svg,
+                r#"  <rect x="{x:.1}" y="{y}" width="{width:.1}" height="{BAR_HEIGHT}" fill="{color}"><title>Day {} {label}: {part}</title></rect>"#,
+                timing.day
+            );
+            x += width;
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let total = std::time::Duration::from_nanos(timing.total_nanos().round() as u64);
+        let _ = writeln!(
+            svg,
+            r##"  <text x="{:.1}" y="{text_y}" fill="#888">{total:.1?}</text>"##,
+            x + 6.0
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::render;
+    use crate::{
+        day,
+        template::timings::{PartTiming, Timing, Timings},
+    };
+
+    fn part(median_nanos: f64) -> Option<PartTiming> {
+        Some(PartTiming {
+            median_nanos,
+            std_dev_nanos: 0.0,
+            samples: 100,
+            memory_peak_bytes: None,
+        })
+    }
+
+    #[test]
+    fn renders_bars() {
+        let timings = Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: part(1e6),
+                    part_2: part(1e6),
+                    calibration_nanos: None,
+                },
+                Timing {
+                    day: day!(6),
+                    parse: part(1e6),
+                    part_1: part(1e6),
+                    part_2: part(2e6),
+                    calibration_nanos: None,
+                },
+            ],
+        };
+
+        let svg = render(&timings);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // 3 legend entries and 5 segments.
+        assert_eq!(svg.matches("<rect ").count(), 8);
+        // the slowest day spans the whole bar area of 488px, its part 2 half of it.
+        assert!(svg.contains(r##"<rect x="308.0" y="56" width="244.0" height="16" fill="#bf616a"><title>Day 06 Part 2: 2.0ms</title></rect>"##));
+        assert!(svg.contains(">2.0ms</text>"));
+        assert!(svg.contains(">4.0ms</text>"));
+    }
+}
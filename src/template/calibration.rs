@@ -0,0 +1,46 @@
+//! A fixed workload whose duration describes how fast the current machine is.
+//! Timings that were measured on different machines, or under different load, become comparable after scaling
+//! them by the ratio of the calibration durations.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Number of times the workload is run, the median duration is reported.
+const ROUNDS: usize = 7;
+
+const ITERATIONS: u64 = 2_000_000;
+
+/// Runs the calibration workload and returns its median duration.
+pub fn calibrate() -> Duration {
+    let mut durations: Vec<Duration> = (0..ROUNDS)
+        .map(|_| {
+            let timer = Instant::now();
+            black_box(workload(black_box(ITERATIONS)));
+            timer.elapsed()
+        })
+        .collect();
+
+    durations.sort_unstable();
+    durations[ROUNDS / 2]
+}
+
+/// Mixes integer arithmetic, branches and cache-resident memory accesses, like a typical solution.
+fn workload(iterations: u64) -> u64 {
+    let mut table = [0_u64; 1024];
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    for i in 0..iterations {
+        // xorshift64, see: Marsaglia, "Xorshift RNGs" (2003).
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+
+        let slot = (state % 1024) as usize;
+        table[slot] = if state & 1 == 0 {
+            table[slot].wrapping_add(i)
+        } else {
+            table[slot] ^ state
+        };
+    }
+
+    table.iter().fold(0, |acc, x| acc.wrapping_add(*x))
+}
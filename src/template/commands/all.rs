@@ -1,5 +1,11 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::{process, time::Duration};
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::{all_days, run_multi::run_multi, Executor};
+
+pub fn handle(executor: Executor, timeout: Option<Duration>) {
+    let summary = run_multi(&all_days().collect(), executor, false, timeout);
+
+    if !summary.is_success() {
+        process::exit(1);
+    }
 }
@@ -0,0 +1,145 @@
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+    process,
+};
+
+use crate::template::{
+    puzzle::{self, Section},
+    Day,
+};
+
+/// Extracts the examples of `day` from its puzzle description and fills in their expected answers.
+/// Examples that were already extracted are kept, so running it again after part two unlocks adds the part two
+/// example. `block` overrides the (1-based) code block that is used for the next extracted example.
+pub fn handle(day: Day, block: Option<usize>) {
+    let puzzle_path = format!("data/puzzles/{day}.md");
+    let module_path = format!("src/bin/{day}.rs");
+
+    let Ok(markdown) = fs::read_to_string(&puzzle_path) else {
+        eprintln!("Could not read \"{puzzle_path}\". Try running \"cargo download {day}\" first.");
+        process::exit(1);
+    };
+
+    let mut module = fs::read_to_string(&module_path).ok();
+    if module.is_none() {
+        println!("Module \"{module_path}\" not found, expected answers are not filled in.");
+    }
+
+    let mut block = block;
+    let mut part_one_example: Option<String> = None;
+
+    for section in puzzle::sections(&markdown) {
+        let (example_path, func) = match section.part {
+            1 => (format!("data/examples/{day}.txt"), "part_one"),
+            _ => (format!("data/examples/{day}-2.txt"), "part_two"),
+        };
+
+        let existing = fs::read_to_string(&example_path)
+            .ok()
+            .filter(|text| !text.trim().is_empty());
+
+        let example = if let Some(existing) = existing {
+            println!(
+                "Keeping the example of part {} in \"{example_path}\".",
+                section.part
+            );
+            Some(existing)
+        } else {
+            let choice = choose(&section, block.take());
+            match choice.map(|index| &section.code_blocks[index].text) {
+                Some(text) if part_one_example.as_ref() != Some(text) => {
+                    if let Err(e) = fs::write(&example_path, text) {
+                        eprintln!("Failed to write example file: {e}");
+                        process::exit(1);
+                    }
+                    println!(
+                        "🎄 Wrote the example of part {} to \"{example_path}\".",
+                        section.part
+                    );
+                    Some(text.clone())
+                }
+                _ => None,
+            }
+        };
+
+        // part two uses the example of part one, unless it has its own.
+        let example_number = (section.part == 2 && example.is_some()).then_some(2);
+        if section.part == 1 {
+            part_one_example = example;
+        }
+
+        if let (Some(answer), Some(source)) = (&section.answer, &module) {
+            if let Some(filled) = puzzle::fill_expected(source, func, example_number, answer) {
+                println!(
+                    "🎄 Expecting {answer} for the example of part {}.",
+                    section.part
+                );
+                module = Some(filled);
+            }
+        }
+    }
+
+    if let Some(module) = module {
+        if let Err(e) = fs::write(&module_path, module) {
+            eprintln!("Failed to write module file: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Lists the code blocks of a part and returns the one to use as its example.
+/// In a terminal, the suggested block can be overridden, `-` skips the part.
+fn choose(section: &Section, block: Option<usize>) -> Option<usize> {
+    if section.code_blocks.is_empty() {
+        return None;
+    }
+
+    let suggested = section.likely_example();
+
+    println!("Code blocks of part {}:", section.part);
+    for (index, code_block) in section.code_blocks.iter().enumerate() {
+        let marker = if suggested == Some(index) { "→" } else { " " };
+        println!(
+            "{marker} [{}] {} ({} lines)",
+            index + 1,
+            code_block.preview(40),
+            code_block.text.lines().count()
+        );
+    }
+
+    if let Some(block) = block {
+        return (1..=section.code_blocks.len())
+            .contains(&block)
+            .then(|| block - 1)
+            .or_else(|| {
+                eprintln!("Code block {block} does not exist, using the suggested one.");
+                suggested
+            });
+    }
+
+    if !io::stdin().is_terminal() {
+        return suggested;
+    }
+
+    let default = suggested.map_or("-".to_string(), |index| (index + 1).to_string());
+    print!("Example of part {} [{default}]: ", section.part);
+    let _ = io::stdout().flush();
+
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line).is_err() {
+        return suggested;
+    }
+
+    match line.trim() {
+        "" => suggested,
+        "-" => None,
+        choice => match choice.parse::<usize>() {
+            Ok(block) if (1..=section.code_blocks.len()).contains(&block) => Some(block - 1),
+            _ => {
+                eprintln!("Code block {choice} does not exist, using the suggested one.");
+                suggested
+            }
+        },
+    }
+}
@@ -1,6 +1,10 @@
 pub mod all;
 pub mod download;
+pub mod examples;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod stars;
 pub mod time;
+#[cfg(feature = "today")]
+pub mod today;
@@ -1,15 +1,20 @@
 use std::process;
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, aoc_client, Day};
 
 pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
-    }
+    if aoc_client::uses_aoc_cli() {
+        if aoc_cli::check().is_err() {
+            eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+            process::exit(1);
+        }
 
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
+        if let Err(e) = aoc_cli::read(day) {
+            eprintln!("failed to call aoc-cli: {e}");
+            process::exit(1);
+        };
+    } else if let Err(e) = aoc_client::read(day) {
+        eprintln!("failed to read day {day}: {e}");
         process::exit(1);
-    };
+    }
 }
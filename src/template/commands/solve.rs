@@ -1,8 +1,34 @@
 use std::process::{Command, Stdio};
 
 use crate::template::Day;
+use crate::visualize::{DELAY_OPTION, RENDER_OPTION, VISUALIZE_OPTION};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+/// Controls how a solution shows what it is doing, see `visualize` and `render`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Visualization {
+    /// Draw frames to the terminal.
+    pub terminal: bool,
+    /// Write images to `data/renders/`.
+    pub render: bool,
+    /// Delay between frames in milliseconds.
+    pub delay: Option<u64>,
+}
+
+impl Visualization {
+    fn is_enabled(&self) -> bool {
+        self.terminal || self.render
+    }
+}
+
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    accept: bool,
+    timeout: Option<u64>,
+    visualization: Visualization,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -16,6 +42,10 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push("--release".to_string());
     }
 
+    if visualization.is_enabled() {
+        cmd_args.extend(["--features".to_string(), "visualize".to_string()]);
+    }
+
     cmd_args.push("--".to_string());
 
     if let Some(submit_part) = submit_part {
@@ -23,6 +53,28 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    if accept {
+        cmd_args.push("--accept".to_string());
+    }
+
+    if let Some(timeout) = timeout {
+        cmd_args.push("--timeout".to_string());
+        cmd_args.push(timeout.to_string());
+    }
+
+    if visualization.terminal {
+        cmd_args.push(VISUALIZE_OPTION.to_string());
+    }
+
+    if visualization.render {
+        cmd_args.push(RENDER_OPTION.to_string());
+    }
+
+    if let Some(delay) = visualization.delay {
+        cmd_args.push(DELAY_OPTION.to_string());
+        cmd_args.push(delay.to_string());
+    }
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -0,0 +1,104 @@
+use std::{
+    env, fs, process,
+    time::{Duration, SystemTime},
+};
+
+use crate::template::{
+    aoc_client::AocClient,
+    leaderboard::{Leaderboard, LEADERBOARD_FILE_PATH},
+    readme_stars,
+};
+
+/// The website asks to not request a leaderboard more often than this.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Shows the private leaderboard `AOC_LEADERBOARD_ID` (by default, the one of `AOC_USER_ID`).
+/// With `offline`, the cached leaderboard is shown. With `readme`, the stars of `AOC_USER_ID` (by default, the
+/// owner of the leaderboard) are written to the readme.
+pub fn handle(offline: bool, readme: bool) {
+    let json = if offline {
+        read_cached().unwrap_or_else(|| {
+            eprintln!(
+                "No cached leaderboard found in \"{LEADERBOARD_FILE_PATH}\". Run \"cargo stars\" first."
+            );
+            process::exit(1);
+        })
+    } else {
+        fetch()
+    };
+
+    let leaderboard = match Leaderboard::try_from(json.as_str()) {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => {
+            eprintln!("Failed to read leaderboard: {e}");
+            process::exit(1);
+        }
+    };
+
+    print!("{}", leaderboard.render());
+
+    if readme {
+        let user_id = env_id("AOC_USER_ID");
+        let Some(member) = leaderboard.member(user_id) else {
+            eprintln!(
+                "Member {} is not on the leaderboard.",
+                user_id.unwrap_or(leaderboard.owner_id)
+            );
+            process::exit(1);
+        };
+
+        match readme_stars::update(&leaderboard, member) {
+            Ok(()) => println!(
+                "Updated readme with the {} stars of {}.",
+                member.stars,
+                member.display_name()
+            ),
+            Err(e) => {
+                eprintln!("Failed to update readme with stars: {e:?}");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Downloads the leaderboard, unless the cached one is recent enough.
+fn fetch() -> String {
+    let age = fs::metadata(LEADERBOARD_FILE_PATH)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    if let Some(cached) = age
+        .filter(|&age| age < REFRESH_INTERVAL)
+        .and_then(|_| read_cached())
+    {
+        println!("Using the leaderboard downloaded less than 15 minutes ago.\n");
+        return cached;
+    }
+
+    let Some(id) = env_id("AOC_LEADERBOARD_ID").or_else(|| env_id("AOC_USER_ID")) else {
+        eprintln!("Set AOC_LEADERBOARD_ID or AOC_USER_ID to the id of your private leaderboard.");
+        process::exit(1);
+    };
+
+    let json = AocClient::from_env().and_then(|client| client.leaderboard_json(id));
+    match json {
+        Ok(json) => {
+            if let Err(e) = fs::write(LEADERBOARD_FILE_PATH, &json) {
+                eprintln!("Failed to cache leaderboard: {e}");
+            }
+            json
+        }
+        Err(e) => {
+            eprintln!("Failed to download leaderboard: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn read_cached() -> Option<String> {
+    fs::read_to_string(LEADERBOARD_FILE_PATH).ok()
+}
+
+fn env_id(name: &str) -> Option<u64> {
+    env::var(name).ok().and_then(|id| id.trim().parse().ok())
+}
@@ -1,15 +1,100 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+use std::process;
+use std::time::Duration;
 
+use crate::template::baseline::{Baseline, Change, STORED_BASELINE};
+use crate::template::calibration::calibrate;
+use crate::template::history::{sparkline, Run};
+use crate::template::protocol::PARSE_PART;
+use crate::template::regression::{find_regressions, DEFAULT_TOLERANCE};
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, readme_benchmarks, Day, Executor, ANSI_BOLD, ANSI_RESET};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
+/// Options of `cargo time --check`, which fails if a part became slower than its stored timing.
+#[derive(Clone, Copy, Debug)]
+pub struct CheckOptions {
+    /// Allowed slowdown as a fraction of the stored timing.
+    pub tolerance: f64,
+    /// Scale the new timings by the speed of this machine relative to the one that stored the timings.
+    pub calibrate: bool,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions {
+            tolerance: DEFAULT_TOLERANCE,
+            calibrate: false,
+        }
+    }
+}
+
+/// Baselines that `cargo time` compares the new timings against, or saves them as.
+#[derive(Clone, Debug, Default)]
+pub struct BaselineOptions {
+    /// Name of the baseline to compare against.
+    pub compare: Option<String>,
+    /// Name of the baseline to save the new timings as.
+    pub save: Option<String>,
+}
+
+pub fn handle(
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    timeout: Option<Duration>,
+    executor: Executor,
+    baselines: BaselineOptions,
+    check: Option<CheckOptions>,
+) {
+    let stored_timings = match Timings::read_from_file() {
+        Ok(timings) => timings,
+        Err(e) if store => {
+            eprintln!("Refusing to overwrite stored timings: {e}");
+            process::exit(1);
+        }
+        Err(e) if check.is_some() => {
+            eprintln!("Cannot check for regressions: {e}");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Warning: stored timings are ignored, {e}");
+            Timings::default()
+        }
+    };
+
+    if baselines.save.as_deref() == Some(STORED_BASELINE) {
+        eprintln!(
+            "Baseline `{STORED_BASELINE}` is read from `data/timings.json`, update it with `cargo time --store` instead."
+        );
+        process::exit(1);
+    }
+
+    // NOTE: read the baseline up front, so a typo does not waste a benchmark run.
+    let compare_with = baselines
+        .compare
+        .as_deref()
+        .map(|name| match read_baseline(name) {
+            Ok(Some(baseline)) => (name, baseline),
+            Ok(None) => {
+                let command = if name == STORED_BASELINE {
+                    "cargo time --all --store".to_string()
+                } else {
+                    format!("cargo time --save-baseline {name}")
+                };
+                eprintln!("Baseline `{name}` does not exist. Create it with `{command}`.");
+                process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        });
 
     let days_to_run = day.map_or_else(
         || {
-            if run_all {
+            if run_all || check.is_some() || baselines.compare.is_some() || baselines.save.is_some()
+            {
                 all_days().collect()
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
@@ -21,12 +106,41 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let summary = run_multi(&days_to_run, executor, true, timeout);
+    let is_success = summary.is_success();
+    let mut timings = summary.timings.unwrap();
+    let measured = Baseline {
+        measurements: summary.measurements,
+    };
+
+    if let Some((name, baseline)) = compare_with {
+        print_comparison(name, &baseline, &measured);
+    }
+
+    if let Some(name) = &baselines.save {
+        println!();
+        match save_baseline(name, &measured) {
+            Ok(()) => println!("Saved baseline `{name}`."),
+            Err(e) => eprintln!("Failed to save baseline `{name}`: {e}"),
+        }
+    }
+
+    let passed_check =
+        check.is_none_or(|check| run_check(&stored_timings, &measured, check) && is_success);
 
     if store {
+        // NOTE: the calibration is stored per day, days that were not benched keep the one they were stored with.
+        let calibration = calibration_nanos();
+        for timing in &mut timings.data {
+            timing.calibration_nanos = Some(calibration);
+        }
         let merged_timings = stored_timings.merge(&timings);
         merged_timings.store_file().unwrap();
 
+        if let Err(e) = Run::new(measured.measurements.clone()).append() {
+            eprintln!("Failed to append to the timings history: {e}");
+        }
+
         println!();
         match readme_benchmarks::update(merged_timings) {
             Ok(()) => {
@@ -37,4 +151,216 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
             }
         }
     }
+
+    if !passed_check {
+        process::exit(1);
+    }
+}
+
+/// Prints the timings of `day` in every stored run, oldest first.
+pub fn history(day: Day) {
+    let runs = match Run::read_all() {
+        Ok(runs) => runs,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+
+    let runs: Vec<&Run> = runs
+        .iter()
+        .filter(|run| run.measurements.iter().any(|m| m.day == day))
+        .collect();
+
+    if runs.is_empty() {
+        println!(
+            "No stored timings for day {day} yet. Store some with `cargo time {day} --store`."
+        );
+        return;
+    }
+
+    println!("{ANSI_BOLD}History of day {day}:{ANSI_RESET}");
+    println!(
+        "{:<16} {:<9} {:>10} {:>10} {:>10} {:>10} {:>8}",
+        "Date", "Commit", "Parse", "Part 1", "Part 2", "Total", "Δ%"
+    );
+
+    let mut totals: Vec<f64> = Vec::with_capacity(runs.len());
+    for run in &runs {
+        let median = |part: u8| run.get(day, part).map(|m| m.median_nanos);
+        let total: f64 = [PARSE_PART, 1, 2].into_iter().filter_map(median).sum();
+        let percent = match totals.last() {
+            Some(&previous) if previous > 0.0 => {
+                format!("{:+.1}%", (total / previous - 1.0) * 100.0)
+            }
+            _ => "-".into(),
+        };
+        let cell = |part: u8| median(part).map_or("-".into(), format_nanos);
+
+        println!(
+            "{:<16} {:<9} {:>10} {:>10} {:>10} {:>10} {percent:>8}",
+            run.date(),
+            run.short_commit(),
+            cell(PARSE_PART),
+            cell(1),
+            cell(2),
+            format_nanos(total)
+        );
+        totals.push(total);
+    }
+
+    println!("\nTrend: {}", sparkline(&totals));
+    if let Some(run) = runs.last() {
+        let unknown = "unknown".to_string();
+        println!(
+            "Latest run: {}, {}",
+            run.rustc.as_ref().unwrap_or(&unknown),
+            run.cpu.as_ref().unwrap_or(&unknown)
+        );
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn calibration_nanos() -> f64 {
+    calibrate().as_nanos() as f64
+}
+
+/// Prints a report of the parts that regressed beyond the tolerance. Returns `true` if there are none.
+fn run_check(stored: &Timings, measured: &Baseline, check: CheckOptions) -> bool {
+    let calibration = check.calibrate.then(calibration_nanos);
+
+    if calibration.is_some() {
+        let uncalibrated: Vec<String> = stored
+            .data
+            .iter()
+            .filter(|timing| timing.calibration_nanos.is_none())
+            .filter(|timing| measured.measurements.iter().any(|m| m.day == timing.day))
+            .map(|timing| timing.day.to_string())
+            .collect();
+
+        if !uncalibrated.is_empty() {
+            eprintln!(
+                "\nWarning: the stored timings of day(s) {} have no calibration yet, store them with `cargo time --all --store`. Checking them without calibration.",
+                uncalibrated.join(", ")
+            );
+        }
+    }
+
+    let measured_days: BTreeSet<Day> = measured.measurements.iter().map(|m| m.day).collect();
+    let unstored: Vec<String> = measured_days
+        .iter()
+        .filter(|day| !stored.data.iter().any(|timing| timing.day == **day))
+        .map(Day::to_string)
+        .collect();
+
+    if !measured_days.is_empty() && unstored.len() == measured_days.len() {
+        eprintln!(
+            "\nNo stored timings to check against, store them with `cargo time --all --store`."
+        );
+        return false;
+    }
+    if !unstored.is_empty() {
+        eprintln!(
+            "\nWarning: day(s) {} have no stored timings and are not checked, store them with `cargo time --store`.",
+            unstored.join(", ")
+        );
+    }
+
+    let regressions =
+        find_regressions(stored, &measured.measurements, check.tolerance, calibration);
+    let tolerance = check.tolerance * 100.0;
+    let calibrated = if calibration.is_some() {
+        ", calibrated"
+    } else {
+        ""
+    };
+
+    println!("\n{ANSI_BOLD}Regression check{ANSI_RESET} (tolerance {tolerance}%{calibrated}):");
+
+    if regressions.is_empty() {
+        println!("✔ No part is more than {tolerance}% slower than its stored timing.");
+        return true;
+    }
+
+    for regression in &regressions {
+        println!("✖ {regression}");
+    }
+    println!(
+        "{} part(s) are more than {tolerance}% slower than their stored timings.",
+        regressions.len()
+    );
+    false
+}
+
+/// Reads the baseline `name`. The baseline `stored` is made of the stored timings.
+fn read_baseline(name: &str) -> Result<Option<Baseline>, String> {
+    if name == STORED_BASELINE {
+        let baseline = Baseline::from(&Timings::read_from_file()?);
+        Ok((!baseline.measurements.is_empty()).then_some(baseline))
+    } else {
+        Baseline::read(name)
+    }
+}
+
+/// Merges the new measurements into the baseline `name`, so benching a single day keeps the others.
+fn save_baseline(name: &str, measured: &Baseline) -> Result<(), String> {
+    let existing = Baseline::read(name)?.unwrap_or_default();
+    existing
+        .merge(measured)
+        .store(name)
+        .map_err(|e| e.to_string())
+}
+
+fn print_comparison(name: &str, baseline: &Baseline, measured: &Baseline) {
+    println!("\n{ANSI_BOLD}Comparison with baseline `{name}`:{ANSI_RESET}");
+    println!(
+        "{:<4} {:<5} {:>12} {:>12} {:>13} {:>8}  Change",
+        "Day", "Part", "Baseline", "Current", "Δ", "Δ%"
+    );
+
+    for new in &measured.measurements {
+        let part = match new.part {
+            PARSE_PART => "Parse".to_string(),
+            part => part.to_string(),
+        };
+
+        let Some(old) = baseline.get(new.day, new.part) else {
+            println!(
+                "{:<4} {part:<5} {:>12} {:>12} {:>13} {:>8}  {}",
+                new.day.to_string(),
+                "-",
+                format_nanos(new.median_nanos),
+                "-",
+                "-",
+                Change::Unknown
+            );
+            continue;
+        };
+
+        let delta = new.median_nanos - old.median_nanos;
+        let percent = if old.median_nanos > 0.0 {
+            format!("{:+.1}%", delta / old.median_nanos * 100.0)
+        } else {
+            "-".into()
+        };
+
+        println!(
+            "{:<4} {part:<5} {:>12} {:>12} {:>13} {percent:>8}  {}",
+            new.day.to_string(),
+            format_nanos(old.median_nanos),
+            format_nanos(new.median_nanos),
+            format_delta(delta),
+            Change::between(old, new)
+        );
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos.abs().round() as u64))
+}
+
+fn format_delta(nanos: f64) -> String {
+    let sign = if nanos < 0.0 { '-' } else { '+' };
+    format!("{sign}{}", format_nanos(nanos))
 }
@@ -0,0 +1,150 @@
+use std::{
+    fmt::Display,
+    io::{stdout, Write},
+    path::Path,
+    process, thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::template::{
+    aoc_cli::{self, AocCommandError},
+    aoc_client::{self, AocClientError},
+    commands::{download, examples, read, scaffold},
+    Day,
+};
+
+/// Seconds to wait between downloads of a puzzle that did not unlock yet, in order.
+/// The puzzle may not be served right at midnight, the website asks to not request it repeatedly.
+const RETRY_DELAYS_SECS: [u64; 8] = [1, 2, 4, 8, 15, 30, 60, 60];
+
+/// A day that unlocked less than this many minutes ago is set up by `--wait`, if it was not already.
+const RECENT_UNLOCK_MINS: i64 = 10;
+
+/// Scaffolds, downloads and reads the current day. With `wait`, waits for the next day to unlock first.
+pub fn handle(wait: bool, with_examples: bool) {
+    let day = if wait {
+        wait_for_unlock()
+    } else {
+        let Some(day) = Day::today() else {
+            eprintln!(
+                "`today` command can only be run between the 1st and \
+                the 25th of december. Please use `scaffold` with a specific day."
+            );
+            process::exit(1)
+        };
+        scaffold::handle(day, false);
+        download::handle(day);
+        day
+    };
+
+    if with_examples {
+        examples::handle(day, None);
+    }
+    read::handle(day);
+}
+
+/// Scaffolds the next day, waits until it unlocks and downloads it. Returns the day.
+/// If the current day unlocked within the last [`RECENT_UNLOCK_MINS`] minutes and is not set up yet, it is set up
+/// right away instead.
+fn wait_for_unlock() -> Day {
+    let now = Utc::now();
+    let recent = Day::latest_unlock(now).filter(|(day, unlock)| {
+        now - *unlock < TimeDelta::minutes(RECENT_UNLOCK_MINS) && !is_set_up(*day)
+    });
+
+    let Some((day, unlock)) = recent.or_else(|| Day::next_unlock(now)) else {
+        eprintln!(
+            "`today --wait` can only be run between the 30th of november and \
+            the 24th of december. Please use `scaffold` with a specific day."
+        );
+        process::exit(1)
+    };
+
+    // the module may have been scaffolded by an earlier run that was interrupted.
+    if !Path::new(&format!("src/bin/{day}.rs")).exists() {
+        scaffold::handle(day, false);
+    }
+
+    countdown(day, unlock);
+    download_when_available(day);
+    day
+}
+
+/// Whether the module and the input of `day` exist.
+fn is_set_up(day: Day) -> bool {
+    Path::new(&format!("src/bin/{day}.rs")).exists()
+        && Path::new(&format!("data/inputs/{day}.txt")).exists()
+}
+
+/// Shows the time until `unlock` and returns once it is reached.
+fn countdown(day: Day, unlock: DateTime<Utc>) {
+    while let Ok(remaining) = (unlock - Utc::now()).to_std() {
+        let secs = remaining.as_secs();
+        print!(
+            "\r⏳ Day {day} unlocks in {}:{:02}:{:02} ",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        );
+        let _ = stdout().flush();
+        // sleep until the next full second, so the countdown ends right at the unlock.
+        let fraction = Duration::from_nanos(u64::from(remaining.subsec_nanos()));
+        thread::sleep(if fraction.is_zero() {
+            Duration::from_secs(1)
+        } else {
+            fraction
+        });
+    }
+    println!("\r🎄 Day {day} unlocked!              ");
+}
+
+/// Downloads `day`, retrying with increasing delays while the puzzle is not available yet.
+fn download_when_available(day: Day) {
+    if aoc_client::uses_aoc_cli() {
+        if aoc_cli::check().is_err() {
+            eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+            process::exit(1);
+        }
+        // aoc-cli does not report why a download failed, so any failed download is retried.
+        retry_download(
+            day,
+            || aoc_cli::download(day).map(|_| ()),
+            |e| matches!(e, AocCommandError::BadExitStatus(_)),
+        );
+    } else {
+        retry_download(
+            day,
+            || aoc_client::download(day),
+            AocClientError::is_transient,
+        );
+    }
+}
+
+/// Calls `download` until it succeeds, waiting [`RETRY_DELAYS_SECS`] after each transient error.
+/// Exits if an error is not transient, or the puzzle is still not available after the last delay.
+fn retry_download<E: Display>(
+    day: Day,
+    download: impl Fn() -> Result<(), E>,
+    is_transient: impl Fn(&E) -> bool,
+) {
+    let mut delays = RETRY_DELAYS_SECS.into_iter();
+    loop {
+        match download() {
+            Ok(()) => return,
+            Err(e) if is_transient(&e) => {
+                let Some(delay) = delays.next() else {
+                    eprintln!("failed to download day {day}: {e}");
+                    process::exit(1);
+                };
+                eprintln!("Day {day} is not available yet ({e}). Retrying in {delay}s...");
+                thread::sleep(Duration::from_secs(delay));
+            }
+            Err(e) => {
+                eprintln!("failed to download day {day}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 #[cfg(feature = "today")]
-use chrono::{Datelike, FixedOffset, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Utc};
 
 #[cfg(feature = "today")]
 const SERVER_UTC_OFFSET: i32 = -5;
@@ -55,6 +55,38 @@ impl Day {
             None
         }
     }
+
+    /// Returns the day that unlocks next and when, if it is between the 1st and the 25th of december, `None`
+    /// otherwise. Puzzles unlock at midnight in the timezone of the server.
+    pub fn next_unlock(now: DateTime<Utc>) -> Option<(Self, DateTime<Utc>)> {
+        Self::unlock_of(Self::server_date(now)?.succ_opt()?)
+    }
+
+    /// Returns the day that unlocked last and when, if it is between the 1st and the 25th of december, `None`
+    /// otherwise.
+    pub fn latest_unlock(now: DateTime<Utc>) -> Option<(Self, DateTime<Utc>)> {
+        Self::unlock_of(Self::server_date(now)?)
+    }
+
+    fn server_date(now: DateTime<Utc>) -> Option<NaiveDate> {
+        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
+        Some(now.with_timezone(&offset).date_naive())
+    }
+
+    /// The day that unlocks on `date` in the timezone of the server, and when it unlocks.
+    fn unlock_of(date: NaiveDate) -> Option<(Self, DateTime<Utc>)> {
+        if date.month() != 12 || date.day() > 25 {
+            return None;
+        }
+
+        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
+        let unlock = date
+            .and_hms_opt(0, 0, 0)?
+            .and_local_timezone(offset)
+            .single()?
+            .with_timezone(&Utc);
+        Some((Self::new(u8::try_from(date.day()).ok()?)?, unlock))
+    }
 }
 
 impl Display for Day {
@@ -187,6 +219,55 @@ mod tests {
         assert_eq!(iter.next(), Some(Day(25)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    #[cfg(feature = "today")]
+    fn next_unlock() {
+        use chrono::{TimeZone, Utc};
+
+        let unlock = |y, m, d, h, min| {
+            Day::next_unlock(Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap())
+                .map(|(day, at)| (day.into_inner(), at.to_rfc3339()))
+        };
+
+        // 23:59 on november 30th in UTC-5.
+        assert_eq!(
+            unlock(2024, 12, 1, 4, 59),
+            Some((1, "2024-12-01T05:00:00+00:00".into()))
+        );
+        assert_eq!(
+            unlock(2024, 12, 1, 5, 0),
+            Some((2, "2024-12-02T05:00:00+00:00".into()))
+        );
+        assert_eq!(
+            unlock(2024, 12, 24, 12, 0),
+            Some((25, "2024-12-25T05:00:00+00:00".into()))
+        );
+        assert_eq!(unlock(2024, 12, 25, 5, 0), None);
+        assert_eq!(unlock(2024, 11, 30, 4, 59), None);
+    }
+
+    #[test]
+    #[cfg(feature = "today")]
+    fn latest_unlock() {
+        use chrono::{TimeZone, Utc};
+
+        let unlock = |y, m, d, h, min, s| {
+            Day::latest_unlock(Utc.with_ymd_and_hms(y, m, d, h, min, s).unwrap())
+                .map(|(day, at)| (day.into_inner(), at.to_rfc3339()))
+        };
+
+        // 30 seconds after day 1 unlocked.
+        assert_eq!(
+            unlock(2024, 12, 1, 5, 0, 30),
+            Some((1, "2024-12-01T05:00:00+00:00".into()))
+        );
+        assert_eq!(
+            unlock(2024, 12, 26, 4, 59, 59),
+            Some((25, "2024-12-25T05:00:00+00:00".into()))
+        );
+        assert_eq!(unlock(2024, 12, 1, 4, 59, 59), None);
+    }
 }
 
 /* -------------------------------------------------------------------------- */
@@ -0,0 +1,306 @@
+//! A log of every stored `cargo time` run, to follow how solutions evolve over time.
+//! `cargo time --store` appends a line to `data/timings-history.jsonl`, with the commit and machine that measured
+//! it. `data/timings.json` keeps the latest timing of every day, `cargo time --history <day>` prints all of them.
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, Write},
+    process::Command,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::{baseline::Measurement, Day};
+
+static HISTORY_FILE_PATH: &str = "./data/timings-history.jsonl";
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Timings of a stored `cargo time` run, and where they were measured.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub commit: Option<String>,
+    /// Whether tracked files had uncommitted changes.
+    pub dirty: bool,
+    pub rustc: Option<String>,
+    pub cpu: Option<String>,
+    pub measurements: Vec<Measurement>,
+}
+
+impl Run {
+    /// Describes a run that measured `measurements` just now, on this machine and commit.
+    pub fn new(measurements: Vec<Measurement>) -> Self {
+        Run {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            commit: command_output("git", &["rev-parse", "HEAD"]),
+            dirty: command_output("git", &["status", "--porcelain", "--untracked-files=no"])
+                .is_some_and(|status| !status.is_empty()),
+            rustc: command_output(
+                &env::var("RUSTC").unwrap_or_else(|_| "rustc".into()),
+                &["--version"],
+            ),
+            cpu: cpu_model(),
+            measurements,
+        }
+    }
+
+    /// Appends the run to the history file.
+    pub fn append(&self) -> Result<(), io::Error> {
+        let json = JsonValue::from(self)
+            .stringify()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(HISTORY_FILE_PATH)?;
+        writeln!(file, "{json}")
+    }
+
+    /// Reads all runs, oldest first. If the history file is not present, returns no runs.
+    pub fn read_all() -> Result<Vec<Self>, String> {
+        match fs::read_to_string(HISTORY_FILE_PATH) {
+            Ok(jsonl) => parse_history(&jsonl),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(format!("could not read timings history: {e}")),
+        }
+    }
+
+    /// Formats the timestamp as `YYYY-MM-DD HH:MM` in UTC.
+    pub fn date(&self) -> String {
+        let days = i64::try_from(self.timestamp / 86_400).unwrap_or(i64::MAX);
+        let (year, month, day) = civil_from_days(days);
+        let minutes = self.timestamp % 86_400 / 60;
+        format!(
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+            minutes / 60,
+            minutes % 60
+        )
+    }
+
+    /// The abbreviated commit hash, followed by `*` if the working tree was dirty.
+    pub fn short_commit(&self) -> String {
+        let commit = self.commit.as_deref().map_or("-", |c| &c[..c.len().min(8)]);
+        if self.dirty {
+            format!("{commit}*")
+        } else {
+            commit.to_string()
+        }
+    }
+
+    pub fn get(&self, day: Day, part: u8) -> Option<&Measurement> {
+        self.measurements
+            .iter()
+            .find(|m| m.day == day && m.part == part)
+    }
+}
+
+fn parse_history(jsonl: &str) -> Result<Vec<Run>, String> {
+    jsonl
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            Run::try_from(line)
+                .map_err(|e| format!("timings history is invalid in line {}: {e}", index + 1))
+        })
+        .collect()
+}
+
+/// Runs a command and returns its trimmed standard output, if it succeeded.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Reads the CPU model from `/proc/cpuinfo`. Returns `None` on other platforms.
+fn cpu_model() -> Option<String> {
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()?
+        .lines()
+        .find(|line| line.starts_with("model name"))?
+        .split_once(':')
+        .map(|(_, model)| model.trim().to_string())
+}
+
+/// Converts days since the unix epoch to a `(year, month, day)` date.
+/// See: Howard Hinnant, "chrono-Compatible Low-Level Date Algorithms".
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Draws `values` as a line of bars, scaled between their minimum and maximum.
+pub fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    values
+        .iter()
+        .map(|value| {
+            if max > min {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let level = ((value - min) / (max - min) * (SPARKS.len() - 1) as f64).round();
+                SPARKS[level as usize]
+            } else {
+                SPARKS[0]
+            }
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Run> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Run) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        let optional = |s: &Option<String>| s.clone().map_or(JsonValue::Null, JsonValue::String);
+
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert("commit".into(), optional(&value.commit));
+        map.insert("dirty".into(), JsonValue::Boolean(value.dirty));
+        map.insert("rustc".into(), optional(&value.rustc));
+        map.insert("cpu".into(), optional(&value.cpu));
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.measurements.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&str> for Run {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(value).or(Err("not valid JSON."))?;
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected run to be a JSON object.")?;
+
+        let optional = |key: &str| match json.get(key) {
+            None => Ok(None),
+            Some(v) if v.is_null() => Ok(None),
+            Some(v) => v
+                .get::<String>()
+                .cloned()
+                .map(Some)
+                .ok_or(format!("expected run.{key} to be null or string.")),
+        };
+
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("expected run.timestamp to be a number.")?;
+
+        let dirty = json
+            .get("dirty")
+            .and_then(|v| v.get::<bool>().copied())
+            .ok_or("expected run.dirty to be a boolean.")?;
+
+        let measurements = json
+            .get("data")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("expected run.data to be an array.")?
+            .iter()
+            .map(Measurement::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Run {
+            timestamp: timestamp as u64,
+            commit: optional("commit")?,
+            dirty,
+            rustc: optional("rustc")?,
+            cpu: optional("cpu")?,
+            measurements,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_history, sparkline, Run};
+    use crate::{day, template::baseline::Measurement};
+
+    fn run(timestamp: u64, median_nanos: f64) -> Run {
+        Run {
+            timestamp,
+            commit: Some("0a5cc68d3f1e2b4c".into()),
+            dirty: true,
+            rustc: Some("rustc 1.83.0 (90b35a623 2024-11-26)".into()),
+            cpu: None,
+            measurements: vec![Measurement {
+                day: day!(6),
+                part: 2,
+                median_nanos,
+                std_dev_nanos: 10.0,
+                samples: 100,
+            }],
+        }
+    }
+
+    #[test]
+    fn parses_history() {
+        let runs = [
+            run(1_733_011_200, 37_000_000.0),
+            run(1_733_097_600, 1_200_000.0),
+        ];
+        let jsonl: String = runs
+            .iter()
+            .map(|run| tinyjson::JsonValue::from(run).stringify().unwrap() + "\n")
+            .collect();
+
+        assert_eq!(parse_history(&jsonl).unwrap(), runs);
+        assert_eq!(runs[1].date(), "2024-12-02 00:00");
+        assert_eq!(runs[1].short_commit(), "0a5cc68d*");
+        assert_eq!(runs[1].get(day!(6), 2).unwrap().median_nanos, 1_200_000.0);
+
+        let error = parse_history(&format!("{jsonl}\n{{ \"timestamp\": 0 }}\n")).unwrap_err();
+        assert!(error.contains("line 4"), "{error}");
+    }
+
+    #[test]
+    fn formats_dates() {
+        let date = |timestamp| {
+            Run {
+                timestamp,
+                ..run(0, 0.0)
+            }
+            .date()
+        };
+        assert_eq!(date(0), "1970-01-01 00:00");
+        assert_eq!(date(951_827_696), "2000-02-29 12:34");
+        assert_eq!(date(1_798_761_599), "2026-12-31 23:59");
+    }
+
+    #[test]
+    fn draws_sparklines() {
+        assert_eq!(sparkline(&[8.0, 1.0, 4.5, 1.0]), "█▁▅▁");
+        assert_eq!(sparkline(&[3.0, 3.0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
@@ -0,0 +1,367 @@
+//! Reads private leaderboards of the Advent of Code website, to show the progress of its members.
+//! `cargo stars` caches the leaderboard in `data/leaderboard.json`, so it can be shown without a session.
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::{all_days, Day};
+
+pub static LEADERBOARD_FILE_PATH: &str = "./data/leaderboard.json";
+
+/// Puzzles unlock at midnight in UTC-5.
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+/// A member of a private leaderboard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub local_score: u64,
+    pub stars: u64,
+    /// When each star was collected, by day and part, in seconds since the unix epoch.
+    pub completions: BTreeMap<(Day, u8), u64>,
+}
+
+impl Member {
+    /// The name of the member, anonymous members are shown like on the website.
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    /// The number of stars collected for `day`.
+    pub fn stars_of(&self, day: Day) -> usize {
+        (1..=2)
+            .filter(|&part| self.completions.contains_key(&(day, part)))
+            .count()
+    }
+}
+
+/// A private leaderboard, see `https://adventofcode.com/<year>/leaderboard/private`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub year: u16,
+    pub owner_id: u64,
+    /// Members, ordered by their local score.
+    pub members: Vec<Member>,
+}
+
+impl Leaderboard {
+    /// The member with `id`, or the owner of the leaderboard.
+    pub fn member(&self, id: Option<u64>) -> Option<&Member> {
+        let id = id.unwrap_or(self.owner_id);
+        self.members.iter().find(|member| member.id == id)
+    }
+
+    /// When the puzzle of `day` unlocked, in seconds since the unix epoch.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn unlock_timestamp(&self, day: Day) -> u64 {
+        let days = days_from_civil(i64::from(self.year), 12, u32::from(day.into_inner()));
+        days as u64 * 86_400 + UNLOCK_HOUR_UTC * 3600
+    }
+
+    /// How long `member` took to collect a star, in seconds since the puzzle unlocked.
+    pub fn completion_secs(&self, member: &Member, day: Day, part: u8) -> Option<u64> {
+        member
+            .completions
+            .get(&(day, part))
+            .map(|ts| ts.saturating_sub(self.unlock_timestamp(day)))
+    }
+
+    /// The last day that any member collected a star for.
+    fn last_day(&self) -> Option<Day> {
+        self.members
+            .iter()
+            .filter_map(|member| member.completions.keys().map(|(day, _)| *day).max())
+            .max()
+    }
+
+    /// Renders the members with their stars per day, and the times they took to collect them.
+    pub fn render(&self) -> String {
+        let days: Vec<Day> = all_days()
+            .take_while(|&day| self.last_day().is_some_and(|last| day <= last))
+            .collect();
+        let width = self
+            .members
+            .iter()
+            .map(|member| member.display_name().chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Member".len());
+
+        let mut out = String::new();
+        // NOTE: writing to a string cannot fail.
+        let _ = writeln!(
+            out,
+            "Leaderboard {} ({} members)\n",
+            self.year,
+            self.members.len()
+        );
+        let day_numbers: String = days
+            .iter()
+            .map(|day| char::from_digit(u32::from(day.into_inner() % 10), 10).unwrap_or(' '))
+            .collect();
+        let _ = writeln!(
+            out,
+            "{:>3}  {:<width$}  {:>5}  {:>5}  {day_numbers}",
+            "#", "Member", "Score", "Stars"
+        );
+        for (rank, member) in self.members.iter().enumerate() {
+            let stars: String = days
+                .iter()
+                .map(|&day| match member.stars_of(day) {
+                    2 => '★',
+                    1 => '☆',
+                    _ => '·',
+                })
+                .collect();
+            let _ = writeln!(
+                out,
+                "{:>3}  {:<width$}  {:>5}  {:>5}  {stars}",
+                rank + 1,
+                member.display_name(),
+                member.local_score,
+                member.stars
+            );
+        }
+
+        let members: Vec<&Member> = self.members.iter().filter(|m| m.stars > 0).collect();
+        if members.is_empty() {
+            return out;
+        }
+
+        let _ = writeln!(out, "\nCompletion times (part 1 / part 2, since unlock)\n");
+        let names: Vec<String> = members.iter().map(|m| m.display_name()).collect();
+        let column = names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(17);
+        let _ = write!(out, "Day");
+        for name in &names {
+            let _ = write!(out, "  {name:>column$}");
+        }
+        let _ = writeln!(out);
+
+        for &day in &days {
+            let _ = write!(out, "{:>3}", day.into_inner());
+            for member in &members {
+                let time = |part| {
+                    self.completion_secs(member, day, part)
+                        .map_or("-".to_string(), format_completion)
+                };
+                let cell = format!("{} / {}", time(1), time(2));
+                let _ = write!(out, "  {cell:>column$}");
+            }
+            let _ = writeln!(out);
+        }
+
+        out
+    }
+}
+
+/// Formats the time it took to collect a star, e.g. `12:34` or `1:02:03`. Times of a day or longer are `>24h`.
+pub fn format_completion(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    match hours {
+        0 => format!("{minutes:02}:{seconds:02}"),
+        1..=23 => format!("{hours}:{minutes:02}:{seconds:02}"),
+        _ => ">24h".into(),
+    }
+}
+
+/// Converts a date to days since the unix epoch.
+/// See: Howard Hinnant, "chrono-Compatible Low-Level Date Algorithms".
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl TryFrom<&str> for Leaderboard {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(value).or(Err("not valid JSON."))?;
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected leaderboard to be a JSON object.")?;
+
+        let year = json
+            .get("event")
+            .and_then(|v| v.get::<String>())
+            .and_then(|event| event.parse().ok())
+            .ok_or("expected leaderboard.event to be a year.")?;
+
+        let owner_id = json
+            .get("owner_id")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("expected leaderboard.owner_id to be a number.")?;
+
+        let mut members = json
+            .get("members")
+            .and_then(|v| v.get::<HashMap<String, JsonValue>>())
+            .ok_or("expected leaderboard.members to be an object.")?
+            .values()
+            .map(Member::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        members.sort_by(|a, b| {
+            b.local_score
+                .cmp(&a.local_score)
+                .then(b.stars.cmp(&a.stars))
+                .then(a.id.cmp(&b.id))
+        });
+
+        Ok(Leaderboard {
+            year,
+            owner_id: *owner_id as u64,
+            members,
+        })
+    }
+}
+
+impl TryFrom<&JsonValue> for Member {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected member to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>())
+                .map(|n| *n as u64)
+                .ok_or(format!("expected member.{key} to be a number."))
+        };
+
+        let name = match json.get("name") {
+            None => None,
+            Some(v) if v.is_null() => None,
+            Some(v) => Some(
+                v.get::<String>()
+                    .ok_or("expected member.name to be null or a string.")?
+                    .clone(),
+            ),
+        };
+
+        let mut completions = BTreeMap::new();
+        let days = json
+            .get("completion_day_level")
+            .and_then(|v| v.get::<HashMap<String, JsonValue>>())
+            .ok_or("expected member.completion_day_level to be an object.")?;
+        for (day, parts) in days {
+            let day = day
+                .parse()
+                .ok()
+                .and_then(Day::new)
+                .ok_or(format!("expected `{day}` to be a day."))?;
+            let parts = parts
+                .get::<HashMap<String, JsonValue>>()
+                .ok_or("expected the completions of a day to be an object.")?;
+            for (part, star) in parts {
+                let part = part
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|part| *part == 1 || *part == 2)
+                    .ok_or(format!("expected `{part}` to be 1 or 2."))?;
+                let timestamp = star
+                    .get::<HashMap<String, JsonValue>>()
+                    .and_then(|star| star.get("get_star_ts"))
+                    .and_then(|v| v.get::<f64>())
+                    .ok_or("expected a star to have a get_star_ts number.")?;
+                completions.insert((day, part), *timestamp as u64);
+            }
+        }
+
+        Ok(Member {
+            id: number("id")?,
+            name,
+            local_score: number("local_score")?,
+            stars: number("stars")?,
+            completions,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_completion, Leaderboard};
+    use crate::day;
+
+    const LEADERBOARD: &str = r#"{
+        "event": "2024",
+        "owner_id": 3031,
+        "members": {
+            "3031": {
+                "id": 3031, "name": "Ferris", "local_score": 10, "stars": 3, "global_score": 0,
+                "last_star_ts": 1733119200,
+                "completion_day_level": {
+                    "1": {
+                        "1": { "get_star_ts": 1733030154, "star_index": 1 },
+                        "2": { "get_star_ts": 1733031723, "star_index": 2 }
+                    },
+                    "2": { "1": { "get_star_ts": 1733119200, "star_index": 3 } }
+                }
+            },
+            "4711": {
+                "id": 4711, "name": null, "local_score": 0, "stars": 0, "global_score": 0,
+                "last_star_ts": 0, "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn parses_leaderboards() {
+        let leaderboard = Leaderboard::try_from(LEADERBOARD).unwrap();
+        assert_eq!(leaderboard.year, 2024);
+        assert_eq!(leaderboard.members.len(), 2);
+
+        let owner = leaderboard.member(None).unwrap();
+        assert_eq!(owner.display_name(), "Ferris");
+        assert_eq!(owner.stars_of(day!(1)), 2);
+        assert_eq!(owner.stars_of(day!(2)), 1);
+        assert_eq!(
+            leaderboard.member(Some(4711)).unwrap().display_name(),
+            "(anonymous user #4711)"
+        );
+
+        // 2024-12-01 05:00 UTC
+        assert_eq!(leaderboard.unlock_timestamp(day!(1)), 1_733_029_200);
+        assert_eq!(leaderboard.completion_secs(owner, day!(1), 1), Some(954));
+        assert_eq!(leaderboard.completion_secs(owner, day!(2), 2), None);
+    }
+
+    #[test]
+    fn renders_leaderboards() {
+        let rendered = Leaderboard::try_from(LEADERBOARD).unwrap().render();
+        assert!(rendered.contains("\n  1  Ferris                     10      3  ★☆\n"));
+        assert!(rendered.contains("\n  2  (anonymous user #4711)      0      0  ··\n"));
+        assert!(rendered.contains("\nDay             Ferris\n"));
+        assert!(rendered.contains("\n  1      15:54 / 42:03\n"));
+        assert!(rendered.contains("\n  2        1:00:00 / -\n"));
+    }
+
+    #[test]
+    fn formats_completion_times() {
+        assert_eq!(format_completion(61), "01:01");
+        assert_eq!(format_completion(3723), "1:02:03");
+        assert_eq!(format_completion(86_400), ">24h");
+    }
+}
@@ -0,0 +1,300 @@
+//! Converts the HTML of puzzle pages to Markdown, which is stored in `data/puzzles/<day>.md`.
+//! Only the elements that puzzle descriptions use are supported: headings, paragraphs, lists, links, emphasis,
+//! inline code and code blocks. Other tags are dropped, their text is kept.
+
+/// A piece of an HTML document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Open { name: String, tag: &'a str },
+    Close(String),
+    Text(String),
+}
+
+/// Splits `html` into tags and (decoded) text. Comments and doctypes are skipped.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if let Some(end) = rest.starts_with('<').then(|| rest.find('>')).flatten() {
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+
+            let name: String = tag
+                .trim_start_matches('/')
+                .chars()
+                .take_while(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_ascii_lowercase();
+
+            if tag.starts_with('/') {
+                tokens.push(Token::Close(name));
+            } else if !tag.starts_with('!') {
+                tokens.push(Token::Open { name, tag });
+            }
+        } else {
+            // a `<` without a closing `>` does not start a tag, so the rest is text.
+            let end = match rest.find('<') {
+                Some(0) | None => rest.len(),
+                Some(end) => end,
+            };
+            tokens.push(Token::Text(decode_entities(&rest[..end])));
+            rest = &rest[end..];
+        }
+    }
+
+    tokens
+}
+
+/// Decodes the named entities that puzzles use, and numeric entities.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let character = entity.and_then(|(entity, _)| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        });
+
+        match (character, entity) {
+            (Some(character), Some((_, end))) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Reads the value of `attribute` from an opening tag, e.g. `href` from `a href="/2024/day/1/input"`.
+fn attribute<'a>(tag: &'a str, attribute: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{attribute}=\""))? + attribute.len() + 2;
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+/// Converts an HTML fragment to Markdown.
+pub fn from_html(html: &str) -> String {
+    let mut markdown = String::new();
+    // text of the current block, whitespace is collapsed when the block ends.
+    let mut block = String::new();
+    let mut links: Vec<String> = vec![];
+    let mut pre = 0;
+    let mut code = 0;
+    // emphasized inline code, e.g. the answer of an example, is written as *`41`*.
+    let mut is_emphasized_code = false;
+
+    // list items are separated by a single line break, other blocks by an empty line.
+    let end_block = |markdown: &mut String, block: &mut String| {
+        let text = block.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            markdown.push_str(&text);
+            markdown.push_str(if text.starts_with("- ") { "\n" } else { "\n\n" });
+        }
+        block.clear();
+    };
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => block.push_str(&text),
+            Token::Open { name, tag } => match name.as_str() {
+                "h1" | "h2" | "h3" => {
+                    end_block(&mut markdown, &mut block);
+                    block.push_str("## ");
+                }
+                "p" | "article" | "div" => end_block(&mut markdown, &mut block),
+                "li" => {
+                    end_block(&mut markdown, &mut block);
+                    block.push_str("- ");
+                }
+                "pre" => {
+                    end_block(&mut markdown, &mut block);
+                    pre += 1;
+                }
+                "code" if pre > 0 => {}
+                "code" => {
+                    code += 1;
+                    block.push('`');
+                }
+                "em" | "strong" | "b" | "i" if code == 0 && pre == 0 => block.push('*'),
+                "em" if code > 0 && pre == 0 && block.ends_with('`') => {
+                    block.pop();
+                    block.push_str("*`");
+                    is_emphasized_code = true;
+                }
+                "a" => {
+                    links.push(attribute(tag, "href").unwrap_or_default().to_string());
+                    block.push('[');
+                }
+                "br" => block.push('\n'),
+                _ => {}
+            },
+            Token::Close(name) => match name.as_str() {
+                "h1" | "h2" | "h3" | "p" | "li" | "article" | "div" => {
+                    end_block(&mut markdown, &mut block);
+                }
+                "ul" | "ol" => {
+                    end_block(&mut markdown, &mut block);
+                    markdown.push('\n');
+                }
+                "pre" => {
+                    pre -= 1;
+                    let text = block.trim_end_matches('\n');
+                    markdown.push_str(&format!("```\n{text}\n```\n\n"));
+                    block.clear();
+                }
+                "code" if pre > 0 => {}
+                "code" => {
+                    code -= 1;
+                    block.push_str(if is_emphasized_code { "`*" } else { "`" });
+                    is_emphasized_code = false;
+                }
+                "em" | "strong" | "b" | "i" if code == 0 && pre == 0 => block.push('*'),
+                "a" => {
+                    let href = links.pop().unwrap_or_default();
+                    block.push_str(&format!("]({href})"));
+                }
+                _ => {}
+            },
+        }
+    }
+
+    end_block(&mut markdown, &mut block);
+    format!("{}\n", markdown.trim_end())
+}
+
+/// Converts a puzzle page to Markdown: the descriptions of both parts and the answers that were given so far.
+pub fn from_puzzle_html(html: &str) -> String {
+    let main = html
+        .find("<main>")
+        .map(|start| {
+            let end = html[start..]
+                .find("</main>")
+                .map_or(html.len(), |end| start + end);
+            &html[start + 6..end]
+        })
+        .unwrap_or(html);
+
+    let mut fragments = vec![];
+    let mut rest = main;
+    loop {
+        let article = rest.find("<article");
+        let answer = rest.find("<p>Your puzzle answer was");
+
+        let (start, end_tag) = match (article, answer) {
+            (Some(article), Some(answer)) if answer < article => (answer, "</p>"),
+            (Some(article), _) => (article, "</article>"),
+            (None, Some(answer)) => (answer, "</p>"),
+            (None, None) => break,
+        };
+
+        let end = rest[start..]
+            .find(end_tag)
+            .map_or(rest.len(), |end| start + end + end_tag.len());
+        fragments.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+
+    from_html(&fragments.concat())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{decode_entities, from_html, from_puzzle_html};
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &amp;&amp; &#39;c&#x27; &unknown; &"),
+            "a <b> && 'c' &unknown; &"
+        );
+    }
+
+    #[test]
+    fn keeps_unterminated_tags_as_text() {
+        assert_eq!(from_html("<p>a <em>b</em> <"), "a *b* <\n");
+        assert_eq!(from_html("<p>a <br"), "a <br\n");
+        assert_eq!(from_html("<"), "<\n");
+    }
+
+    #[test]
+    fn converts_descriptions() {
+        let html = r#"<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2>
+<p>The <em>Chief Historian</em> is always present.
+See <a href="/2024/about" target="_blank">the about page</a>.</p>
+<pre><code>3   4
+<em>4</em>   3
+</code></pre>
+<ul><li>Pair up <code>1</code> and <code>3</code>, a distance of <code><em>2</em></code>.</li><li>Next.</li></ul>
+<p>What is the total distance?</p>
+</article>"#;
+
+        let expected = "## --- Day 1: Historian Hysteria ---
+
+The *Chief Historian* is always present. See [the about page](/2024/about).
+
+```
+3   4
+4   3
+```
+
+- Pair up `1` and `3`, a distance of *`2`*.
+- Next.
+
+What is the total distance?
+";
+        assert_eq!(from_html(html), expected);
+    }
+
+    #[test]
+    fn extracts_puzzles_and_answers() {
+        let html = r#"<!DOCTYPE html><html><body><header><h1>Advent of Code</h1></header><main>
+<script>window.x = 1;</script>
+<article class="day-desc"><h2>--- Day 6: Guard Gallivant ---</h2><p>Part one.</p></article>
+<p>Your puzzle answer was <code>4559</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Part two.</p></article>
+<p>Your puzzle answer was <code>1604</code>.</p>
+<p>Both parts of this puzzle are complete! They provide two gold stars: **</p>
+<form method="post" action="6/answer"><input type="hidden" name="level" value="2"/></form>
+</main></body></html>"#;
+
+        let expected = "## --- Day 6: Guard Gallivant ---
+
+Part one.
+
+Your puzzle answer was `4559`.
+
+## --- Part Two ---
+
+Part two.
+
+Your puzzle answer was `1604`.
+";
+        assert_eq!(from_puzzle_html(html), expected);
+    }
+}
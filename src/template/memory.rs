@@ -0,0 +1,136 @@
+//! Measures the peak heap usage of solution parts.
+//!
+//! [`PeakAlloc`] wraps the system allocator and counts allocated bytes while a measurement is running. The main
+//! binary installs it, so peaks are known for days that run in-process. Solution binaries keep the system allocator,
+//! since `dhat` replaces it when profiling. Outside of measurements, the allocator only checks a flag, so benching
+//! is not slowed down.
+//!
+//! Allocations are counted on every thread, so parts that use `rayon` are measured in full. This includes allocations
+//! of other threads that run at the same time, such as the progress output of `cargo all`, so peaks of a few KiB are
+//! approximate.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicBool, AtomicIsize, Ordering},
+};
+
+static IS_INSTALLED: AtomicBool = AtomicBool::new(false);
+static IS_TRACKING: AtomicBool = AtomicBool::new(false);
+/// Bytes allocated since the measurement started, negative if memory that existed before was freed.
+static CURRENT: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+/// A global allocator that tracks the peak heap usage during measurements, see [`measure`].
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: advent_of_code::template::memory::PeakAlloc = advent_of_code::template::memory::PeakAlloc;
+/// ```
+pub struct PeakAlloc;
+
+impl PeakAlloc {
+    #[inline]
+    fn record(delta: isize) {
+        if IS_TRACKING.load(Ordering::Relaxed) {
+            IS_INSTALLED.store(true, Ordering::Relaxed);
+            let current = CURRENT.fetch_add(delta, Ordering::Relaxed) + delta;
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+/// Runs `f` and returns the peak number of bytes allocated at once while it ran, on any thread.
+/// The peak is `None` if [`PeakAlloc`] is not the global allocator.
+/// NOTE: measurements must not overlap, solutions are run one part at a time.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<u64>) {
+    IS_TRACKING.store(true, Ordering::SeqCst);
+    // a probe allocation shows whether the allocator is installed, even if `f` does not allocate.
+    drop(black_box(Box::new(0_u8)));
+    CURRENT.store(0, Ordering::SeqCst);
+    PEAK.store(0, Ordering::SeqCst);
+
+    let result = f();
+
+    IS_TRACKING.store(false, Ordering::SeqCst);
+    let peak = IS_INSTALLED
+        .load(Ordering::SeqCst)
+        .then(|| PEAK.load(Ordering::SeqCst).max(0).unsigned_abs() as u64);
+
+    (result, peak)
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 MiB`.
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_bytes, measure};
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn is_unknown_without_allocator() {
+        // the test binary uses the system allocator.
+        let (result, peak) = measure(|| vec![0_u8; 4096].len());
+        assert_eq!(result, 4096);
+        assert_eq!(peak, None);
+    }
+}
@@ -1,14 +1,32 @@
 use std::{env, fs};
 
 pub mod aoc_cli;
+pub mod aoc_client;
 pub mod commands;
+pub mod memory;
 pub mod runner;
+pub mod submission;
 
+pub use baseline::STORED_BASELINE;
 pub use day::*;
+pub use regression::parse_tolerance;
+pub use run_multi::Executor;
 
+mod answers;
+mod baseline;
+mod benchmark_chart;
+mod calibration;
 mod day;
+mod history;
+mod leaderboard;
+mod markdown;
+mod protocol;
+mod puzzle;
 mod readme_benchmarks;
+mod readme_stars;
+mod regression;
 mod run_multi;
+mod stats;
 mod timings;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
@@ -39,6 +57,10 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Passing `parse = <function>` runs the function once on the input and hands its result to both parts.
+/// Parsing is then timed separately from the parts, e.g. `solution!(5, parse = parse_input)` or
+/// `solution!(5, parse = parse_input, 1)`.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,8 +72,17 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, parse = $parse:expr) => {
+        $crate::solution!(@impl $day, parse = $parse, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, parse = $parse:expr, 1) => {
+        $crate::solution!(@impl $day, parse = $parse, [part_one, 1]);
+    };
+    ($day:expr, parse = $parse:expr, 2) => {
+        $crate::solution!(@impl $day, parse = $parse, [part_two, 2]);
+    };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( parse = $parse:expr, )? $( [$func:expr, $part:expr] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -62,7 +93,75 @@ macro_rules! solution {
         fn main() {
             use $crate::template::runner::*;
             let input = $crate::template::read_file("inputs", DAY);
+            $( let input = run_parse($parse, input.as_str()); )?
             $( run_part($func, &input, DAY, $part); )*
         }
+
+        /// Runs the solution in-process. Used by the solution registry of the main binary.
+        #[allow(dead_code)]
+        pub fn solve_in_process(
+            input: &str,
+            options: $crate::template::runner::RunOptions,
+            on_progress: &mut dyn FnMut($crate::template::runner::Progress),
+        ) {
+            use $crate::template::runner::*;
+            $( let Some(input) = run_parse_in_process($parse, input, options, on_progress) else {
+                return;
+            }; )?
+            $( run_part_in_process($func, &input, $part, options, on_progress); )*
+        }
+    };
+}
+
+/// Generates a unit test per part that checks the part against the expected answers for its example files.
+///
+/// `example => <answer>` reads `data/examples/NN.txt`, `example <n> => <answer>` reads `data/examples/NN-<n>.txt`.
+/// If the solution parses its input, pass the same function as `parse = <function>`.
+///
+/// ```ignore
+/// advent_of_code::examples! {
+///     parse = parse_input,
+///     part_one {
+///         example => Some(11),
+///     }
+///     part_two {
+///         example => Some(31),
+///         example 2 => Some(48),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! examples {
+    (@impl [$parse:path] $( $func:ident { $( example $( $n:literal )? => $expected:expr ),* $(,)? } )*) => {
+        #[cfg(test)]
+        mod examples {
+            $(
+                #[test]
+                fn $func() {
+                    $(
+                        let input = $crate::examples!(@read $( $n )?);
+                        assert_eq!(
+                            super::$func(&$parse(&input)),
+                            $expected,
+                            concat!("example", $( " ", stringify!($n), )? " of ", stringify!($func)),
+                        );
+                    )*
+                }
+            )*
+        }
+    };
+
+    (@read) => {
+        $crate::template::read_file("examples", super::DAY)
+    };
+    (@read $n:literal) => {
+        $crate::template::read_file_part("examples", super::DAY, $n)
+    };
+
+    (parse = $parse:ident, $( $rest:tt )*) => {
+        $crate::examples!(@impl [super::$parse] $( $rest )*);
+    };
+    ($( $rest:tt )*) => {
+        $crate::examples!(@impl [str::to_owned] $( $rest )*);
     };
 }
@@ -0,0 +1,276 @@
+//! Machine-readable protocol between solution binaries and the `all` / `time` commands.
+//! When a solution binary is invoked with `--json`, it prints one JSON record per part to stdout instead of
+//! human-readable output.
+use std::{collections::HashMap, fmt::Display, str::FromStr, time::Duration};
+use tinyjson::JsonValue;
+
+use crate::template::stats::Stats;
+
+/// Command-line flag that switches a solution binary to machine-readable output.
+pub const JSON_FLAG: &str = "--json";
+
+/// Part number used for the records of the parse phase of a solution.
+pub const PARSE_PART: u8 = 0;
+
+/// Returns `true` if the current process was asked to emit machine-readable output.
+pub fn is_json_mode() -> bool {
+    std::env::args().any(|x| x == JSON_FLAG)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The part returned an answer.
+    Solved,
+    /// The part returned `None`.
+    Unsolved,
+    /// The part panicked.
+    Panicked,
+    /// The part exceeded its timeout.
+    TimedOut,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Solved => "solved",
+            Status::Unsolved => "unsolved",
+            Status::Panicked => "panicked",
+            Status::TimedOut => "timed_out",
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::TimedOut => write!(f, "timed out"),
+            status => write!(f, "{}", status.as_str()),
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "solved" => Ok(Status::Solved),
+            "unsolved" => Ok(Status::Unsolved),
+            "panicked" => Ok(Status::Panicked),
+            "timed_out" => Ok(Status::TimedOut),
+            s => Err(format!("unknown part status `{s}`.")),
+        }
+    }
+}
+
+/// The outcome of running a single solution part, or the parse phase if `part` is [`PARSE_PART`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartRecord {
+    pub part: u8,
+    pub status: Status,
+    pub answer: Option<String>,
+    /// Describes why a part failed, only present for panicked or timed out parts.
+    pub message: Option<String>,
+    pub stats: Stats,
+    /// Peak heap usage of the first execution in bytes, if it is known, see [`memory`](super::memory).
+    pub memory_peak_bytes: Option<u64>,
+}
+
+impl PartRecord {
+    /// Serializes the record to a single line of JSON.
+    pub fn to_json_line(&self) -> String {
+        // NOTE: stringifying a JSON value only fails for non-finite numbers, which durations never produce.
+        JsonValue::from(self).stringify().unwrap_or_default()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[allow(clippy::cast_precision_loss)]
+fn nanos(duration: Duration) -> JsonValue {
+    JsonValue::Number(duration.as_nanos() as f64)
+}
+
+impl From<&PartRecord> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &PartRecord) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert(
+            "status".into(),
+            JsonValue::String(value.status.as_str().into()),
+        );
+        map.insert(
+            "answer".into(),
+            match &value.answer {
+                Some(x) => JsonValue::String(x.clone()),
+                None => JsonValue::Null,
+            },
+        );
+        map.insert(
+            "message".into(),
+            match &value.message {
+                Some(x) => JsonValue::String(x.clone()),
+                None => JsonValue::Null,
+            },
+        );
+        map.insert("nanos".into(), nanos(value.stats.median));
+        map.insert(
+            "samples".into(),
+            JsonValue::Number(value.stats.samples as f64),
+        );
+        map.insert(
+            "outliers".into(),
+            JsonValue::Number(value.stats.outliers as f64),
+        );
+        map.insert("min_nanos".into(), nanos(value.stats.min));
+        map.insert("mean_nanos".into(), nanos(value.stats.mean));
+        map.insert("p95_nanos".into(), nanos(value.stats.p95));
+        map.insert("std_dev_nanos".into(), nanos(value.stats.std_dev));
+        map.insert(
+            "memory_peak_bytes".into(),
+            match value.memory_peak_bytes {
+                Some(x) => JsonValue::Number(x as f64),
+                None => JsonValue::Null,
+            },
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl FromStr for PartRecord {
+    type Err = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = JsonValue::from_str(s).or(Err("not valid JSON."))?;
+
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected record to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected record.{key} to be a number."))
+        };
+
+        let duration = |key: &str| number(key).map(|x| Duration::from_nanos(x as u64));
+
+        let status = json
+            .get("status")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected record.status to be a string.")?
+            .parse()?;
+
+        let answer = json
+            .get("answer")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected record.answer to be null or string.")?;
+
+        let message = json
+            .get("message")
+            .map(|v| if v.is_null() { None } else { v.get::<String>() })
+            .ok_or("Expected record.message to be null or string.")?;
+
+        // NOTE: `memory_peak_bytes` is optional, solution binaries built before it was measured do not emit it.
+        let memory_peak_bytes = json
+            .get("memory_peak_bytes")
+            .map_or(Some(None), |v| {
+                if v.is_null() {
+                    Some(None)
+                } else {
+                    v.get::<f64>().map(|x| Some(*x as u64))
+                }
+            })
+            .ok_or("Expected record.memory_peak_bytes to be null or a number.")?;
+
+        Ok(PartRecord {
+            part: number("part")? as u8,
+            status,
+            answer: answer.cloned(),
+            message: message.cloned(),
+            stats: Stats {
+                samples: number("samples")? as u128,
+                outliers: number("outliers")? as u128,
+                min: duration("min_nanos")?,
+                median: duration("nanos")?,
+                mean: duration("mean_nanos")?,
+                p95: duration("p95_nanos")?,
+                std_dev: duration("std_dev_nanos")?,
+            },
+            memory_peak_bytes,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{PartRecord, Status};
+    use crate::template::stats::Stats;
+    use std::time::Duration;
+
+    #[test]
+    fn roundtrips_records() {
+        let record = PartRecord {
+            part: 2,
+            status: Status::Solved,
+            answer: Some("@ ( ) \"quoted\"\nmultiline".into()),
+            message: None,
+            stats: Stats {
+                samples: 100,
+                outliers: 3,
+                min: Duration::from_nanos(70),
+                median: Duration::from_nanos(74),
+                mean: Duration::from_nanos(75),
+                p95: Duration::from_nanos(80),
+                std_dev: Duration::from_nanos(2),
+            },
+            memory_peak_bytes: Some(1_572_864),
+        };
+
+        let line = record.to_json_line();
+        assert_eq!(line.contains('\n'), false);
+        assert_eq!(line.parse::<PartRecord>().unwrap(), record);
+    }
+
+    #[test]
+    fn roundtrips_unsolved_records() {
+        let record = PartRecord {
+            part: 1,
+            status: Status::Unsolved,
+            answer: None,
+            message: None,
+            stats: Stats::single(Duration::from_nanos(10)),
+            memory_peak_bytes: None,
+        };
+
+        assert_eq!(record.to_json_line().parse::<PartRecord>().unwrap(), record);
+    }
+
+    #[test]
+    fn roundtrips_failed_records() {
+        let record = PartRecord {
+            part: 1,
+            status: Status::Panicked,
+            answer: None,
+            message: Some("unknown character: x".into()),
+            stats: Stats::single(Duration::from_nanos(10)),
+            memory_peak_bytes: None,
+        };
+
+        assert_eq!(record.to_json_line().parse::<PartRecord>().unwrap(), record);
+    }
+
+    #[test]
+    fn rejects_non_record_lines() {
+        assert!("Part 1: 42 (74.13ns @ 100 samples)"
+            .parse::<PartRecord>()
+            .is_err());
+        assert!(r#"{ "part": 1 }"#.parse::<PartRecord>().is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Finds the example inputs and their expected answers in a puzzle description (`data/puzzles/<day>.md`).
+//!
+//! Examples are code blocks, usually introduced by "For example:". The answer of an example is emphasized inline
+//! code in the last sentence of a part that has some: "In this example, the guard will visit *`41`* distinct
+//! positions". If that sentence emphasizes several codes, the answer is the first number among them.
+
+/// A code block of a puzzle description.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub text: String,
+    /// The paragraph before the block.
+    pub context: String,
+}
+
+impl CodeBlock {
+    /// Whether the paragraph before the block introduces an example.
+    pub fn is_introduced_as_example(&self) -> bool {
+        self.context.to_ascii_lowercase().contains("example")
+    }
+
+    /// The first line of the block, shortened to `width` characters.
+    pub fn preview(&self, width: usize) -> String {
+        let line = self.text.lines().next().unwrap_or_default();
+        if line.chars().count() > width {
+            format!("{}…", line.chars().take(width).collect::<String>())
+        } else {
+            line.to_string()
+        }
+    }
+}
+
+/// The description of one part of a puzzle.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Section {
+    pub part: u8,
+    pub code_blocks: Vec<CodeBlock>,
+    /// The answer of the example, see [`answer_of`].
+    pub answer: Option<String>,
+}
+
+impl Section {
+    /// The index of the code block that most likely is the example input: the first block that is introduced as
+    /// an example, or in part one, the first block with several lines.
+    pub fn likely_example(&self) -> Option<usize> {
+        let introduced = self
+            .code_blocks
+            .iter()
+            .position(CodeBlock::is_introduced_as_example);
+
+        if self.part == 1 {
+            introduced
+                .or_else(|| {
+                    self.code_blocks
+                        .iter()
+                        .position(|b| b.text.lines().count() > 1)
+                })
+                .or((!self.code_blocks.is_empty()).then_some(0))
+        } else {
+            introduced
+        }
+    }
+}
+
+/// Splits a puzzle description into its parts.
+pub fn sections(markdown: &str) -> Vec<Section> {
+    let mut sections = vec![Section {
+        part: 1,
+        ..Section::default()
+    }];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut fence: Option<Vec<&str>> = None;
+
+    for line in markdown.lines() {
+        let section = sections.last_mut().unwrap();
+
+        if line.starts_with("```") {
+            match fence.take() {
+                Some(lines) => section.code_blocks.push(CodeBlock {
+                    text: lines.iter().map(|line| format!("{line}\n")).collect(),
+                    context: paragraph.join(" "),
+                }),
+                None => fence = Some(vec![]),
+            }
+            continue;
+        }
+
+        if let Some(lines) = fence.as_mut() {
+            lines.push(line);
+        } else if line.starts_with("## --- Part Two ---") {
+            sections.push(Section {
+                part: 2,
+                ..Section::default()
+            });
+            paragraph.clear();
+        } else if line.trim().is_empty() {
+            paragraph.clear();
+        } else {
+            paragraph.push(line);
+            if !line.starts_with("Your puzzle answer was") {
+                if let Some(answer) = answer_of(line) {
+                    section.answer = Some(answer.to_string());
+                }
+            }
+        }
+    }
+
+    sections
+}
+
+/// The emphasized inline code of a sentence that answers the question: the first number, or the first code if none
+/// is a number. In "place *`6`* obstructions, with a `#` at *`3,6`*", the answer is `6`.
+fn answer_of(line: &str) -> Option<&str> {
+    let codes = emphasized_code(line);
+    codes
+        .iter()
+        .find(|code| code.parse::<i64>().is_ok())
+        .or(codes.first())
+        .copied()
+}
+
+/// Finds inline code that is emphasized, e.g. `41` in "visit *`41`* positions".
+fn emphasized_code(line: &str) -> Vec<&str> {
+    let mut found = vec![];
+    let mut rest = line;
+    while let Some(start) = rest.find("*`") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("`*") else {
+            break;
+        };
+        found.push(&rest[..end]);
+        rest = &rest[end + 2..];
+    }
+    found
+}
+
+/// Sets the expected answer of an example in the `examples!` block of a solution, e.g. `example => Some(41),`.
+/// `example` selects the example file, see `examples!`. Only an expected answer of `None` is replaced.
+/// The answer is written as a string if the part returns one, otherwise as a number.
+/// Returns `None` if the solution does not have an example to fill in, or the answer does not fit its return type.
+pub fn fill_expected(
+    module: &str,
+    func: &str,
+    example: Option<u8>,
+    answer: &str,
+) -> Option<String> {
+    let block = module.find("examples!")?;
+    let start = block + module[block..].find(&format!("{func} {{"))?;
+    let end = start + module[start..].find('}')?;
+    let placeholder = start + module[start..end].find("example => None")?;
+
+    let return_type = return_type(module, func).unwrap_or_default();
+    let expected = if return_type.contains("String") {
+        format!("String::from({answer:?})")
+    } else if return_type.contains("str") {
+        format!("{answer:?}")
+    } else if answer.parse::<i64>().is_ok() {
+        answer.to_string()
+    } else {
+        return None;
+    };
+    let example = example.map_or("example".to_string(), |n| format!("example {n}"));
+
+    Some(format!(
+        "{}{example} => Some({expected}){}",
+        &module[..placeholder],
+        &module[placeholder + "example => None".len()..]
+    ))
+}
+
+/// The return type of the function `func`, e.g. `Option<u32>` for `pub fn part_one(input: &str) -> Option<u32> {`.
+fn return_type<'a>(module: &'a str, func: &str) -> Option<&'a str> {
+    let start = module.find(&format!("fn {func}("))?;
+    let signature = &module[start..start + module[start..].find('{')?];
+    let (_, return_type) = signature.rsplit_once("->")?;
+    Some(return_type.trim())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{fill_expected, sections};
+
+    const PUZZLE: &str = "## --- Day 6: Guard Gallivant ---
+
+The map shows the lab. For example:
+
+```
+....#.....
+.........#
+```
+
+The guard moves like this:
+
+```
+^
+```
+
+In this example, the guard will visit *`41`* distinct positions on your map.
+
+Your puzzle answer was `4559`.
+
+## --- Part Two ---
+
+You could place *`6`* different obstructions, with a `#` at *`3,6`*.
+
+Your puzzle answer was *`1604`*.
+";
+
+    #[test]
+    fn finds_examples_and_answers() {
+        let sections = sections(PUZZLE);
+        assert_eq!(sections.len(), 2);
+
+        let part_one = &sections[0];
+        assert_eq!(part_one.code_blocks.len(), 2);
+        assert_eq!(part_one.code_blocks[0].text, "....#.....\n.........#\n");
+        assert_eq!(part_one.code_blocks[0].preview(4), "....…");
+        assert_eq!(part_one.likely_example(), Some(0));
+        assert_eq!(part_one.answer.as_deref(), Some("41"));
+
+        let part_two = &sections[1];
+        assert!(part_two.code_blocks.is_empty());
+        assert_eq!(part_two.likely_example(), None);
+        assert_eq!(part_two.answer.as_deref(), Some("6"));
+    }
+
+    #[test]
+    fn falls_back_to_blocks_with_several_lines() {
+        let sections = sections("Move `>`:\n\n```\n>\n```\n\nThe map:\n\n```\n#.\n.#\n```\n");
+        assert_eq!(sections[0].likely_example(), Some(1));
+        assert_eq!(sections[0].answer, None);
+
+        let password = super::sections("The password is *`abc`*, not *`abd`*.\n");
+        assert_eq!(password[0].answer.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn fills_expected_answers() {
+        let module = "pub fn part_one(input: &str) -> Option<u32> {
+    None
+}
+
+pub fn part_two(input: &str) -> Option<String> {
+    None
+}
+
+advent_of_code::examples! {
+    part_one {
+        example => None,
+    }
+    part_two {
+        example => None,
+    }
+}
+";
+        // the answer does not fit the return type of part one.
+        assert_eq!(fill_expected(module, "part_one", None, "3,6"), None);
+
+        let filled = fill_expected(module, "part_one", None, "41").unwrap();
+        let filled = fill_expected(&filled, "part_two", Some(2), "3,6").unwrap();
+        assert_eq!(
+            filled,
+            "pub fn part_one(input: &str) -> Option<u32> {
+    None
+}
+
+pub fn part_two(input: &str) -> Option<String> {
+    None
+}
+
+advent_of_code::examples! {
+    part_one {
+        example => Some(41),
+    }
+    part_two {
+        example 2 => Some(String::from(\"3,6\")),
+    }
+}
+"
+        );
+        assert_eq!(fill_expected(&filled, "part_one", None, "42"), None);
+    }
+}
@@ -1,12 +1,61 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+use std::{env, fs, io, path::Path, str::FromStr, time::Duration};
 
-use crate::template::timings::Timings;
+use crate::template::benchmark_chart::{self, CHART_PATH};
+use crate::template::memory::format_bytes;
+use crate::template::timings::{PartTiming, Timing, Timings};
 use crate::template::Day;
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
+/// Environment variable that selects the [`TableFormat`], set in `.cargo/config.toml`.
+const TABLE_FORMAT_VAR: &str = "AOC_BENCHMARK_TABLE";
+
+/// Width of the bars in the rich table, in characters.
+const BAR_WIDTH: usize = 10;
+
+/// Partially filled blocks by eighths, see [`bar`].
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Layout of the benchmark table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TableFormat {
+    /// Medians of every part.
+    #[default]
+    Simple,
+    /// Medians of every part, a bar and the share of each day of the total time, the memory peak if it is known
+    /// and a chart in `.assets/benchmarks.svg`.
+    Rich,
+}
+
+impl FromStr for TableFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "simple" => Ok(TableFormat::Simple),
+            "rich" => Ok(TableFormat::Rich),
+            s => Err(format!(
+                "unknown benchmark table format `{s}`, expected `simple` or `rich`."
+            )),
+        }
+    }
+}
+
+impl TableFormat {
+    /// Reads the format from `AOC_BENCHMARK_TABLE`, falling back to the simple table if it is not set or invalid.
+    pub fn from_env() -> Self {
+        match env::var(TABLE_FORMAT_VAR) {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                eprintln!("Warning: {e}");
+                TableFormat::default()
+            }),
+            Err(_) => TableFormat::default(),
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
@@ -21,8 +70,8 @@ impl From<std::io::Error> for Error {
 }
 
 pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
+    pub pos_start: usize,
+    pub pos_end: usize,
 }
 
 #[must_use]
@@ -30,8 +79,9 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+/// Finds the section between the first and last occurrence of `marker`, including the markers.
+pub fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -55,22 +105,115 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
 fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
     let header = format!("{prefix} Benchmarks");
 
+    // only render a parse column if at least one day times its parse phase separately.
+    let has_parse = timings.data.iter().any(|t| t.parse.is_some());
+
+    let mut lines: Vec<String> = vec![MARKER.into(), header, String::new()];
+
+    if has_parse {
+        lines.push("| Day | Parse | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---: | :---:  |".into());
+    } else {
+        lines.push("| Day | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---:  |".into());
+    }
+
+    for timing in timings.data {
+        let path = get_path_for_bin(timing.day);
+        let cell = |part: Option<PartTiming>| part.map_or_else(|| "-".into(), |t| t.to_string());
+        let parse = if has_parse {
+            format!(" `{}` |", cell(timing.parse))
+        } else {
+            String::new()
+        };
+        lines.push(format!(
+            "| [Day {}]({}) |{} `{}` | `{}` |",
+            timing.day.into_inner(),
+            path,
+            parse,
+            cell(timing.part_1),
+            cell(timing.part_2)
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(format!("**Total: {total_millis:.2}ms**"));
+    lines.push(MARKER.into());
+
+    lines.join("\n")
+}
+
+fn construct_rich_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
+    let header = format!("{prefix} Benchmarks");
+    let total_nanos = total_millis * 1_000_000_f64;
+
+    let slowest = timings
+        .data
+        .iter()
+        .max_by(|a, b| a.total_nanos().total_cmp(&b.total_nanos()))
+        .map(|timing| (timing.day, timing.total_nanos()));
+
+    // only render a memory column if the peak of at least one part is known.
+    let has_memory = timings.data.iter().any(|t| memory_peak(t).is_some());
+
     let mut lines: Vec<String> = vec![
         MARKER.into(),
         header,
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        format!("[![Benchmark chart](./{CHART_PATH})](./{CHART_PATH})"),
+        String::new(),
     ];
 
-    for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+    let memory_header = if has_memory { " Memory |" } else { "" };
+    let memory_align = if has_memory { " ---: |" } else { "" };
+    lines.push(format!(
+        "| Day | Parse | Part 1 | Part 2 | Total | Share |{memory_header}"
+    ));
+    lines.push(format!(
+        "| :---: | ---: | ---: | ---: | :--- | ---: |{memory_align}"
+    ));
+
+    for timing in &timings.data {
+        let cell = |part: &Option<PartTiming>| part.map_or_else(|| "-".into(), |t| t.to_string());
+        let day_total = timing.total_nanos();
+
+        let mut day = format!(
+            "[Day {}]({})",
             timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            get_path_for_bin(timing.day)
+        );
+        let mut relative = 0.0;
+        if let Some((slowest_day, slowest_nanos)) = slowest {
+            if slowest_day == timing.day {
+                day = format!("**{day}** 🐢");
+            }
+            if slowest_nanos > 0.0 {
+                relative = day_total / slowest_nanos;
+            }
+        }
+
+        let share = if total_nanos > 0.0 {
+            format!("{:.1}%", day_total / total_nanos * 100.0)
+        } else {
+            "-".into()
+        };
+
+        let memory = if has_memory {
+            format!(
+                " {} |",
+                memory_peak(timing).map_or_else(|| "-".into(), format_bytes)
+            )
+        } else {
+            String::new()
+        };
+
+        lines.push(format!(
+            "| {day} | `{}` | `{}` | `{}` | `{}` {} | {share} |{memory}",
+            cell(&timing.parse),
+            cell(&timing.part_1),
+            cell(&timing.part_2),
+            format_nanos(day_total),
+            bar(relative)
         ));
     }
 
@@ -81,47 +224,113 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+/// Formats a duration like the runner prints it, e.g. `74.1ms`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos.round() as u64))
+}
+
+/// The highest memory peak of the parse phase and both parts of a day.
+fn memory_peak(timing: &Timing) -> Option<u64> {
+    [&timing.parse, &timing.part_1, &timing.part_2]
+        .into_iter()
+        .filter_map(|part| part.and_then(|p| p.memory_peak_bytes))
+        .max()
+}
+
+/// Draws `fraction` of a bar of [`BAR_WIDTH`] characters with block elements, in steps of eighths.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn bar(fraction: f64) -> String {
+    let eighths = (fraction.clamp(0.0, 1.0) * (BAR_WIDTH * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    let remainder = eighths % 8;
+    if remainder > 0 {
+        bar.push(EIGHTHS[remainder]);
+    }
+    // NOTE: a day always gets a visible bar, even if it is orders of magnitude faster than the slowest one.
+    if bar.is_empty() {
+        bar.push(EIGHTHS[1]);
+    }
+    bar
+}
+
+fn update_content(
+    s: &mut String,
+    timings: Timings,
+    total_millis: f64,
+    format: TableFormat,
+) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = match format {
+        TableFormat::Simple => construct_table("##", timings, total_millis),
+        TableFormat::Rich => construct_rich_table("##", timings, total_millis),
+    };
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
 pub fn update(timings: Timings) -> Result<(), Error> {
     let path = "README.md";
+    let format = TableFormat::from_env();
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
+
+    if format == TableFormat::Rich {
+        if let Some(dir) = Path::new(CHART_PATH).parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(CHART_PATH, benchmark_chart::render(&timings))?;
+    }
+
+    update_content(&mut readme, timings, total_millis, format)?;
     fs::write(path, &readme)?;
     Ok(())
 }
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use super::{bar, update_content, TableFormat, MARKER};
+    use crate::{
+        day,
+        template::timings::{PartTiming, Timing, Timings},
+    };
+
+    fn part(median_nanos: f64) -> Option<PartTiming> {
+        Some(PartTiming {
+            median_nanos,
+            std_dev_nanos: 0.0,
+            samples: 100,
+            memory_peak_bytes: None,
+        })
+    }
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    parse: None,
+                    part_1: part(10e6),
+                    part_2: part(20e6),
+                    calibration_nanos: None,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    parse: None,
+                    part_1: part(30e6),
+                    part_2: part(40e6),
+                    calibration_nanos: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: Some("50ms".into()),
-                    total_nanos: 9e+10,
+                    parse: None,
+                    part_1: part(40e6),
+                    part_2: part(50e6),
+                    calibration_nanos: None,
                 },
             ],
         }
@@ -131,28 +340,28 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, TableFormat::Simple).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, TableFormat::Simple).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, TableFormat::Simple).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, TableFormat::Simple).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, TableFormat::Simple).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -160,7 +369,7 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, TableFormat::Simple).unwrap();
         let expected = [
             "foo",
             "bar",
@@ -169,9 +378,9 @@ mod tests {
             "",
             "| Day | Part 1 | Part 2 |",
             "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| [Day 1](./src/bin/01.rs) | `10.0ms` | `20.0ms` |",
+            "| [Day 2](./src/bin/02.rs) | `30.0ms` | `40.0ms` |",
+            "| [Day 4](./src/bin/04.rs) | `40.0ms` | `50.0ms` |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -180,4 +389,67 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_parse() {
+        let mut timings = get_mock_timings();
+        timings.data[1].parse = part(5e6);
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0, TableFormat::Simple).unwrap();
+        let expected = [
+            "<!--- benchmarking table --->",
+            "## Benchmarks",
+            "",
+            "| Day | Parse | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: | :---:  |",
+            "| [Day 1](./src/bin/01.rs) | `-` | `10.0ms` | `20.0ms` |",
+            "| [Day 2](./src/bin/02.rs) | `5.0ms` | `30.0ms` | `40.0ms` |",
+            "| [Day 4](./src/bin/04.rs) | `-` | `40.0ms` | `50.0ms` |",
+            "",
+            "**Total: 190.00ms**",
+            "<!--- benchmarking table --->",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn format_rich_benchmarks() {
+        let mut timings = get_mock_timings();
+        timings.data[1].parse = part(5e6);
+        timings.data[2].part_1 = part(40e6).map(|part| PartTiming {
+            memory_peak_bytes: Some(1_572_864),
+            ..part
+        });
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, timings, 195.0, TableFormat::Rich).unwrap();
+        let expected = [
+            "<!--- benchmarking table --->",
+            "## Benchmarks",
+            "",
+            "[![Benchmark chart](./.assets/benchmarks.svg)](./.assets/benchmarks.svg)",
+            "",
+            "| Day | Parse | Part 1 | Part 2 | Total | Share | Memory |",
+            "| :---: | ---: | ---: | ---: | :--- | ---: | ---: |",
+            "| [Day 1](./src/bin/01.rs) | `-` | `10.0ms` | `20.0ms` | `30.0ms` ███▍ | 15.4% | - |",
+            "| [Day 2](./src/bin/02.rs) | `5.0ms` | `30.0ms` | `40.0ms` | `75.0ms` ████████▍ | 38.5% | - |",
+            "| **[Day 4](./src/bin/04.rs)** 🐢 | `-` | `40.0ms` | `50.0ms` | `90.0ms` ██████████ | 46.2% | 1.5 MiB |",
+            "",
+            "**Total: 195.00ms**",
+            "<!--- benchmarking table --->",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn draws_bars() {
+        assert_eq!(bar(1.0), "██████████");
+        assert_eq!(bar(0.55), "█████▌");
+        assert_eq!(bar(0.0), "▏");
+        assert_eq!("simple".parse(), Ok(TableFormat::Simple));
+        assert!("fancy".parse::<TableFormat>().is_err());
+    }
 }
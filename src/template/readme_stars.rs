@@ -0,0 +1,117 @@
+//! Module that updates the readme with the stars of a leaderboard member.
+//! It writes the same table as the `advent-readme-stars` action, between the same markers.
+use std::fs;
+
+use crate::template::all_days;
+use crate::template::leaderboard::{Leaderboard, Member};
+use crate::template::readme_benchmarks::{locate_table, Error};
+
+static MARKER: &str = "<!--- advent_readme_stars table --->";
+
+fn construct_table(prefix: &str, leaderboard: &Leaderboard, member: &Member) -> String {
+    let header = format!("{prefix} {} Results", leaderboard.year);
+
+    let mut lines: Vec<String> = vec![
+        MARKER.into(),
+        header,
+        String::new(),
+        "| Day | Part 1 | Part 2 |".into(),
+        "| :---: | :---: | :---: |".into(),
+    ];
+
+    for day in all_days().filter(|&day| member.stars_of(day) > 0) {
+        let star = |part| {
+            if member.completions.contains_key(&(day, part)) {
+                "⭐"
+            } else {
+                " "
+            }
+        };
+        lines.push(format!(
+            "| [Day {}](https://adventofcode.com/{}/day/{}) | {} | {} |",
+            day.into_inner(),
+            leaderboard.year,
+            day.into_inner(),
+            star(1),
+            star(2)
+        ));
+    }
+
+    lines.push(MARKER.into());
+    lines.join("\n")
+}
+
+fn update_content(s: &mut String, leaderboard: &Leaderboard, member: &Member) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = construct_table("##", leaderboard, member);
+    s.replace_range(positions.pos_start..positions.pos_end, &table);
+    Ok(())
+}
+
+pub fn update(leaderboard: &Leaderboard, member: &Member) -> Result<(), Error> {
+    let path = "README.md";
+    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    update_content(&mut readme, leaderboard, member)?;
+    fs::write(path, &readme)?;
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{update_content, MARKER};
+    use crate::{
+        day,
+        template::leaderboard::{Leaderboard, Member},
+    };
+
+    fn get_mock_leaderboard() -> Leaderboard {
+        Leaderboard {
+            year: 2024,
+            owner_id: 3031,
+            members: vec![Member {
+                id: 3031,
+                name: Some("Ferris".into()),
+                local_score: 10,
+                stars: 3,
+                completions: BTreeMap::from([
+                    ((day!(1), 1), 1_733_030_154),
+                    ((day!(1), 2), 1_733_031_723),
+                    ((day!(3), 1), 1_733_202_000),
+                ]),
+            }],
+        }
+    }
+
+    #[test]
+    fn format_stars() {
+        let leaderboard = get_mock_leaderboard();
+        let mut s = format!("foo\n{MARKER}\nbar");
+        update_content(&mut s, &leaderboard, &leaderboard.members[0]).unwrap();
+        // updating again replaces the table.
+        update_content(&mut s, &leaderboard, &leaderboard.members[0]).unwrap();
+        let expected = [
+            "foo",
+            "<!--- advent_readme_stars table --->",
+            "## 2024 Results",
+            "",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: |",
+            "| [Day 1](https://adventofcode.com/2024/day/1) | ⭐ | ⭐ |",
+            "| [Day 3](https://adventofcode.com/2024/day/3) | ⭐ |   |",
+            "<!--- advent_readme_stars table --->",
+            "bar",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn errors_if_marker_not_present() {
+        let leaderboard = get_mock_leaderboard();
+        let mut s = "# readme".to_string();
+        update_content(&mut s, &leaderboard, &leaderboard.members[0]).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+//! Detects parts that became slower than their stored timings, for `cargo time --check`.
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::template::{baseline::Measurement, protocol::PARSE_PART, timings::Timings, Day};
+
+/// Tolerance of `cargo time --check`, unless overridden with `--tolerance`.
+pub const DEFAULT_TOLERANCE: f64 = 0.2;
+
+/// Slowdowns below this many nanoseconds are ignored, because timer resolution and noise dominate them.
+const MIN_REGRESSION_NANOS: f64 = 1_000.0;
+
+/// A part whose new timing exceeds its stored timing by more than the tolerance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Regression {
+    pub day: Day,
+    pub part: u8,
+    pub stored_nanos: f64,
+    /// The new timing, scaled to the speed of the machine that measured the stored timings.
+    pub measured_nanos: f64,
+}
+
+impl Display for Regression {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let duration = |nanos: f64| Duration::from_nanos(nanos.round() as u64);
+        let part = match self.part {
+            PARSE_PART => "Parse".to_string(),
+            part => format!("Part {part}"),
+        };
+        write!(
+            f,
+            "Day {} {part}: {:.1?} → {:.1?} ({:+.1}%)",
+            self.day,
+            duration(self.stored_nanos),
+            duration(self.measured_nanos),
+            (self.measured_nanos / self.stored_nanos - 1.0) * 100.0
+        )
+    }
+}
+
+/// Parses a tolerance such as `20%` or `20` into a fraction.
+pub fn parse_tolerance(s: &str) -> Result<f64, String> {
+    let percent: f64 = s
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("invalid tolerance `{s}`, expected a percentage such as `20%`."))?;
+
+    if percent.is_finite() && percent >= 0.0 {
+        Ok(percent / 100.0)
+    } else {
+        Err(format!(
+            "invalid tolerance `{s}`, expected a positive percentage."
+        ))
+    }
+}
+
+/// Compares measured parts against the stored timings. Parts without a stored timing are skipped.
+/// With the `calibration_nanos` of the current machine, measured timings are first scaled by the calibration that
+/// was stored with their day, to account for differences in machine speed. Days without one are not scaled.
+pub fn find_regressions(
+    stored: &Timings,
+    measured: &[Measurement],
+    tolerance: f64,
+    calibration_nanos: Option<f64>,
+) -> Vec<Regression> {
+    measured
+        .iter()
+        .filter_map(|measurement| {
+            let timing = stored
+                .data
+                .iter()
+                .find(|timing| timing.day == measurement.day)?;
+            let stored_nanos = timing.part_nanos(measurement.part)?;
+
+            let scale = match (timing.calibration_nanos, calibration_nanos) {
+                (Some(stored), Some(current)) => stored / current,
+                _ => 1.0,
+            };
+            let measured_nanos = measurement.median_nanos * scale;
+
+            let is_regression = measured_nanos > stored_nanos * (1.0 + tolerance)
+                && measured_nanos - stored_nanos >= MIN_REGRESSION_NANOS;
+
+            is_regression.then_some(Regression {
+                day: measurement.day,
+                part: measurement.part,
+                stored_nanos,
+                measured_nanos,
+            })
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{find_regressions, parse_tolerance};
+    use crate::{
+        day,
+        template::{
+            baseline::Measurement,
+            timings::{PartTiming, Timing, Timings},
+        },
+    };
+
+    fn measurement(part: u8, median_nanos: f64) -> Measurement {
+        Measurement {
+            day: day!(6),
+            part,
+            median_nanos,
+            std_dev_nanos: 0.0,
+            samples: 100,
+        }
+    }
+
+    fn part(median_nanos: f64) -> Option<PartTiming> {
+        Some(PartTiming {
+            median_nanos,
+            std_dev_nanos: 0.0,
+            samples: 100,
+            memory_peak_bytes: None,
+        })
+    }
+
+    #[test]
+    fn parses_tolerances() {
+        assert_eq!(parse_tolerance("20%"), Ok(0.2));
+        assert_eq!(parse_tolerance("5"), Ok(0.05));
+        assert!(parse_tolerance("-5%").is_err());
+        assert!(parse_tolerance("fast").is_err());
+    }
+
+    #[test]
+    fn finds_regressions() {
+        let stored = Timings {
+            data: vec![Timing {
+                day: day!(6),
+                parse: part(75_000.0),
+                part_1: part(120_000.0),
+                part_2: part(1_200_000.0),
+                calibration_nanos: Some(1e6),
+            }],
+        };
+        let measured = [
+            measurement(0, 76_000.0),
+            measurement(1, 150_000.0),
+            measurement(2, 12_000_000.0),
+        ];
+
+        let regressions = find_regressions(&stored, &measured, 0.2, None);
+        assert_eq!(
+            regressions.iter().map(|r| r.part).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(
+            regressions[1].to_string(),
+            "Day 06 Part 2: 1.2ms → 12.0ms (+900.0%)"
+        );
+
+        // the current machine is twice as slow as the one that stored the timings.
+        let regressions = find_regressions(&stored, &measured, 0.2, Some(2e6));
+        assert_eq!(regressions.iter().map(|r| r.part).collect::<Vec<_>>(), [2]);
+    }
+
+    #[test]
+    fn scales_by_the_calibration_of_each_day() {
+        let timing = |day, calibration_nanos| Timing {
+            day,
+            parse: None,
+            part_1: part(100_000.0),
+            part_2: None,
+            calibration_nanos,
+        };
+        let stored = Timings {
+            data: vec![timing(day!(5), None), timing(day!(6), Some(1e6))],
+        };
+        let measured = [
+            Measurement {
+                day: day!(5),
+                ..measurement(1, 150_000.0)
+            },
+            measurement(1, 150_000.0),
+        ];
+
+        // only day 06 was stored on a machine that is twice as fast as the current one.
+        let regressions = find_regressions(&stored, &measured, 0.2, Some(2e6));
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].day, day!(5));
+    }
+
+    #[test]
+    fn ignores_tiny_regressions() {
+        let stored = Timings {
+            data: vec![Timing {
+                day: day!(6),
+                parse: None,
+                part_1: part(100.0),
+                part_2: None,
+                calibration_nanos: None,
+            }],
+        };
+        let measured = [measurement(1, 300.0), measurement(2, 1e9)];
+        assert!(find_regressions(&stored, &measured, 0.2, None).is_empty());
+    }
+}
@@ -1,17 +1,90 @@
-use std::{collections::HashSet, io};
+use std::{collections::HashSet, fmt::Display, io, process::ExitStatus, time::Duration};
 
 use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
-    timings::{Timing, Timings},
+    answers::{Answers, Verdict},
+    baseline::Measurement,
+    protocol::{PartRecord, Status, PARSE_PART},
+    runner::{print_record, Solution},
+    timings::{PartTiming, Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Upper bound for the runtime of a day, relative to the part timeout. Covers parsing and both parts.
+const DAY_BUDGET_FACTOR: u32 = 3;
+
+/// Upper bound for benching a phase, relative to the part timeout. Benching takes at least 10 samples, plus
+/// [`BENCH_BUDGET_SLACK`] for fast phases, which are sampled for about a second.
+const BENCH_BUDGET_FACTOR: u32 = 10;
+const BENCH_BUDGET_SLACK: Duration = Duration::from_secs(2);
+
+/// A part (or a whole day, if `part` is `None`) that did not behave as expected during a run.
+#[derive(Clone, Debug)]
+pub struct Failure {
+    pub day: Day,
+    pub part: Option<u8>,
+    pub reason: String,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.part {
+            Some(PARSE_PART) => write!(f, "Day {} Parse: {}", self.day, self.reason),
+            Some(part) => write!(f, "Day {} Part {}: {}", self.day, part, self.reason),
+            None => write!(f, "Day {}: {}", self.day, self.reason),
+        }
+    }
+}
+
+/// The outcome of running a set of days.
+#[derive(Clone, Debug, Default)]
+pub struct Summary {
+    /// Timings of the run, only present for timed runs.
+    pub timings: Option<Timings>,
+    /// Statistics of every solved part and parse phase.
+    pub measurements: Vec<Measurement>,
+    pub failures: Vec<Failure>,
+}
+
+impl Summary {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Determines how `run_multi` executes solutions.
+#[derive(Clone, Copy)]
+pub enum Executor<'a> {
+    /// Run the solutions of the registry inside the current process.
+    /// Days that are missing from the registry fall back to their binaries, as do all days after a part timed out.
+    InProcess(&'a [Solution]),
+    /// Run every solution in its own binary.
+    Isolated { is_release: bool },
+}
+
+/// Runs the solutions of `days_to_run` one after another.
+/// If `timeout` is set, each part has to finish its first execution within it, and a day that exceeds a budget
+/// derived from it is stopped, see [`day_budget`].
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    executor: Executor,
+    is_timed: bool,
+    timeout: Option<Duration>,
+) -> Summary {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let mut measurements: Vec<Measurement> = vec![];
+    let mut failures: Vec<Failure> = vec![];
+
+    let answers = Answers::read_or_warn();
+
+    let budget = timeout.map(|timeout| day_budget(timeout, is_timed));
 
     let mut need_space = false;
 
+    // NOTE: a part that timed out in-process keeps running, which would skew the measurements of later days.
+    let mut executor = executor;
+
     // NOTE: use non-duplicate, sorted day values.
     all_days()
         .filter(|day| days_to_run.contains(day))
@@ -24,17 +97,132 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let failure_count = failures.len();
+
+            let on_record = |record: &PartRecord| {
+                if matches!(record.status, Status::Panicked | Status::TimedOut) {
+                    print_record(record, None);
+                    failures.push(Failure {
+                        day,
+                        part: Some(record.part),
+                        reason: format!(
+                            "{}: {}",
+                            record.status,
+                            record.message.as_deref().unwrap_or_default()
+                        ),
+                    });
+                    return;
+                }
+
+                if record.part == PARSE_PART {
+                    print_record(record, None);
+                    return;
+                }
+
+                let verdict = answers.check(day, record.part, record.answer.as_deref());
+                print_record(record, Some(&verdict));
+
+                if let Verdict::Wrong { expected } = verdict {
+                    failures.push(Failure {
+                        day,
+                        part: Some(record.part),
+                        reason: format!(
+                            "expected `{expected}`, got `{}`",
+                            record.answer.as_deref().unwrap_or("nothing")
+                        ),
+                    });
+                }
+            };
+
+            let solution = match executor {
+                Executor::InProcess(solutions) => solutions.iter().find(|s| s.day == day),
+                Executor::Isolated { .. } => None,
+            };
+
+            let run = match (solution, executor) {
+                (Some(solution), _) => {
+                    in_process::run_solution(solution, is_timed, timeout, budget, on_record)
+                        .map(Some)
+                }
+                (None, Executor::Isolated { is_release }) => child_commands::run_solution(
+                    day, is_timed, is_release, timeout, budget, on_record,
+                ),
+                // NOTE: fall back to a binary with the same profile as the current process.
+                (None, Executor::InProcess(_)) => child_commands::run_solution(
+                    day,
+                    is_timed,
+                    !cfg!(debug_assertions),
+                    timeout,
+                    budget,
+                    on_record,
+                ),
+            };
+
+            let run = match run {
+                Ok(Some(run)) => run,
+                Ok(None) => {
+                    println!("Not solved.");
+                    return;
+                }
+                Err(e) => {
+                    println!("✖ {e}");
+                    failures.push(Failure {
+                        day,
+                        part: None,
+                        reason: format!("could not run solution: {e}"),
+                    });
+                    return;
+                }
+            };
+
+            let is_detached = matches!(run.outcome, Outcome::TimedOut | Outcome::ExceededBudget(_));
+            if solution.is_some() && is_detached {
+                println!("A part is still running, the remaining days run in their own binaries.");
+                executor = Executor::Isolated {
+                    is_release: !cfg!(debug_assertions),
+                };
+            }
+
+            match run.outcome {
+                Outcome::ExceededBudget(budget) => {
+                    println!("✖ stopped after exceeding {budget:?}");
+                    failures.push(Failure {
+                        day,
+                        part: None,
+                        reason: format!("exceeded the day budget of {budget:?}"),
+                    });
+                }
+                // a failed part already explains a non-zero exit status.
+                Outcome::Exited(status) if !status.success() && failures.len() == failure_count => {
+                    failures.push(Failure {
+                        day,
+                        part: None,
+                        reason: format!("solution exited with {status}"),
+                    });
+                }
+                Outcome::Exited(_) | Outcome::Completed | Outcome::TimedOut => {}
+            }
 
-            if output.is_empty() {
+            if run.records.is_empty() {
                 println!("Not solved.");
             } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+                timings.push(timing_from_records(&run.records, day));
+                measurements.extend(
+                    run.records
+                        .iter()
+                        .filter_map(|record| Measurement::from_record(day, record)),
+                );
             }
         });
 
-    if is_timed {
+    if !failures.is_empty() {
+        println!("\n{ANSI_BOLD}Failures:{ANSI_RESET}");
+        for failure in &failures {
+            println!("{failure}");
+        }
+    }
+
+    let timings = if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
@@ -43,16 +231,65 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
         Some(timings)
     } else {
         None
+    };
+
+    Summary {
+        timings,
+        measurements,
+        failures,
+    }
+}
+
+/// The longest a day may run with the given part timeout. Timed runs also bench the parse phase and both parts
+/// after their first execution, so that a part that hangs or slows down while benching does not stall the run.
+fn day_budget(timeout: Duration, is_timed: bool) -> Duration {
+    let budget = timeout * DAY_BUDGET_FACTOR;
+    if is_timed {
+        budget + (timeout * BENCH_BUDGET_FACTOR + BENCH_BUDGET_SLACK) * 3
+    } else {
+        budget
     }
 }
 
-#[allow(dead_code)]
+/// How often the progress and budget of a running solution are checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How a solution run ended.
+pub enum Outcome {
+    /// The in-process solution returned.
+    Completed,
+    /// The solution binary exited.
+    Exited(ExitStatus),
+    /// The solution ran longer than the given budget and was killed (or detached, if it ran in-process).
+    ExceededBudget(Duration),
+    /// A part of the in-process solution exceeded its timeout, which was already reported as a record.
+    TimedOut,
+}
+
+pub struct SolutionRun {
+    pub records: Vec<PartRecord>,
+    pub outcome: Outcome,
+}
+
 #[derive(Debug)]
 pub enum Error {
     BrokenPipe,
+    BuildFailed,
+    MissingInput(String),
     IO(io::Error),
 }
 
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::BrokenPipe => write!(f, "could not read output of solution."),
+            Error::BuildFailed => write!(f, "could not build solution."),
+            Error::MissingInput(path) => write!(f, "could not read input file \"{path}\"."),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::IO(e)
@@ -64,42 +301,154 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-/// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
+/// Solutions that are compiled into the main binary run on a separate thread of the current process.
+/// This module encapsulates running them and enforcing their timeouts.
+pub mod in_process {
+    use super::{Error, Outcome, SolutionRun, POLL_INTERVAL};
+    use crate::template::{
+        protocol::{PartRecord, Status},
+        runner::{failure_record, Progress, RunOptions, Solution},
+    };
+    use std::{
+        fs,
+        sync::mpsc::{self, RecvTimeoutError},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    /// Run a solution of the registry, calling `on_record` as soon as a part finishes.
+    pub fn run_solution(
+        solution: &Solution,
+        is_timed: bool,
+        timeout: Option<Duration>,
+        budget: Option<Duration>,
+        mut on_record: impl FnMut(&PartRecord),
+    ) -> Result<SolutionRun, Error> {
+        let input_path = format!("data/inputs/{}.txt", solution.day);
+        let input = fs::read_to_string(&input_path).map_err(|_| Error::MissingInput(input_path))?;
+
+        // NOTE: timeouts are enforced below, the solution itself must not end the process.
+        let options = RunOptions {
+            is_timed,
+            is_quiet: true,
+            timeout: None,
+        };
+
+        let solve = solution.solve;
+        let (sender, receiver) = mpsc::channel();
+
+        // NOTE: a thread cannot be stopped, so a solution that exceeds its timeout is detached and keeps running
+        // until the process exits.
+        thread::spawn(move || {
+            solve(&input, options, &mut |progress| {
+                let _ = sender.send(progress);
+            });
+        });
+
+        let started_at = Instant::now();
+        let mut running: Option<(u8, Instant)> = None;
+        let mut records = vec![];
+
+        let outcome = loop {
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(Progress::Started(part)) => running = Some((part, Instant::now())),
+                Ok(Progress::Executed(_)) => running = None,
+                Ok(Progress::Finished(record)) => {
+                    running = None;
+                    on_record(&record);
+                    records.push(record);
+                }
+                // the solution returned.
+                Err(RecvTimeoutError::Disconnected) => break Outcome::Completed,
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            if let (Some(timeout), Some((part, since))) = (timeout, running) {
+                if since.elapsed() > timeout {
+                    let message = format!("exceeded {timeout:?}");
+                    let record = failure_record(part, Status::TimedOut, &message, timeout);
+                    on_record(&record);
+                    records.push(record);
+                    break Outcome::TimedOut;
+                }
+            }
+
+            if let Some(budget) = budget {
+                if started_at.elapsed() > budget {
+                    break Outcome::ExceededBudget(budget);
+                }
+            }
+        };
+
+        Ok(SolutionRun { records, outcome })
+    }
+}
+
+/// Solutions also live in isolated binaries.
+/// This module encapsulates interaction with these binaries, both invoking them as well as reading the
+/// machine-readable records they emit.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{get_path_for_bin, Error, Outcome, SolutionRun, POLL_INTERVAL};
+    use crate::template::{
+        protocol::{PartRecord, JSON_FLAG},
+        runner::TIMEOUT_OPTION,
+        Day,
+    };
     use std::{
+        env,
+        env::consts::EXE_SUFFIX,
         io::{BufRead, BufReader},
-        path::Path,
+        path::{Path, PathBuf},
         process::{Command, Stdio},
+        sync::mpsc::{self, RecvTimeoutError},
         thread,
+        time::{Duration, Instant},
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day, calling `on_record` as soon as a part finishes.
+    /// Returns `None` if the day has not been scaffolded yet.
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        timeout: Option<Duration>,
+        budget: Option<Duration>,
+        mut on_record: impl FnMut(&PartRecord),
+    ) -> Result<Option<SolutionRun>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+            return Ok(None);
         }
 
+        // build ahead of time, so that compilation does not count towards the budget.
+        // the binary is then invoked directly, since killing `cargo run` would not stop it.
         let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        let mut build_args = vec!["build", "--quiet", "--bin", &day_padded];
 
         if is_release {
-            args.push("--release");
+            build_args.push("--release");
         }
 
+        if !Command::new("cargo").args(&build_args).status()?.success() {
+            return Err(Error::BuildFailed);
+        }
+
+        let mut args = vec![JSON_FLAG.to_string()];
+
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+            args.push("--time".into());
+        }
+
+        if let Some(timeout) = timeout {
+            args.push(TIMEOUT_OPTION.into());
+            args.push(timeout.as_secs().to_string());
         }
 
         // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        // forward stderr as well as any stdout line that is not a record, collecting records.
 
-        let mut cmd = Command::new("cargo")
+        let mut cmd = Command::new(get_path_for_binary(day, is_release))
             .args(&args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -108,150 +457,181 @@ pub mod child_commands {
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
-
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
+        let stderr_thread = thread::spawn(move || {
+            stderr.lines().map_while(Result::ok).for_each(|line| {
+                eprintln!("{line}");
             });
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
-        }
-
-        thread.join().unwrap();
-        cmd.wait()?;
+        let (sender, receiver) = mpsc::channel();
 
-        Ok(output)
-    }
+        let stdout_thread = thread::spawn(move || {
+            for line in stdout.lines().map_while(Result::ok) {
+                if let Ok(record) = line.parse::<PartRecord>() {
+                    if sender.send(record).is_err() {
+                        return;
+                    }
+                } else {
+                    println!("{line}");
+                }
+            }
+        });
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
-        let mut timings = super::Timing {
-            day,
-            part_1: None,
-            part_2: None,
-            total_nanos: 0_f64,
-        };
+        let deadline = budget.map(|budget| (budget, Instant::now() + budget));
+        let mut records = vec![];
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
+        let outcome = loop {
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(record) => {
+                    on_record(&record);
+                    records.push(record);
+                    continue;
                 }
+                // stdout was closed, the binary is about to exit.
+                Err(RecvTimeoutError::Disconnected) => break Outcome::Exited(cmd.wait()?),
+                Err(RecvTimeoutError::Timeout) => {}
+            }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+            if let Some((budget, deadline)) = deadline {
+                if Instant::now() > deadline {
+                    cmd.kill()?;
+                    cmd.wait()?;
+                    break Outcome::ExceededBudget(budget);
                 }
+            }
+        };
 
-                timings.total_nanos += nanos;
-            });
+        stdout_thread.join().unwrap();
+        stderr_thread.join().unwrap();
 
-        timings
+        Ok(Some(SolutionRun { records, outcome }))
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    fn get_path_for_binary(day: Day, is_release: bool) -> PathBuf {
+        let target_dir = env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".into());
+        let profile = if is_release { "release" } else { "debug" };
+        Path::new(&target_dir)
+            .join(profile)
+            .join(format!("{day}{EXE_SUFFIX}"))
+    }
+}
+
+/// Builds the timing of a day from its records, including the parse phase if present.
+/// Parts that were not solved do not contribute.
+pub fn timing_from_records(records: &[PartRecord], day: Day) -> Timing {
+    let mut timings = Timing {
+        day,
+        parse: None,
+        part_1: None,
+        part_2: None,
+        calibration_nanos: None,
+    };
+
+    records
+        .iter()
+        .filter(|record| record.status == Status::Solved)
+        .for_each(|record| {
+            let timing = Some(PartTiming::from(record));
+
+            match record.part {
+                PARSE_PART => timings.parse = timing,
+                1 => timings.part_1 = timing,
+                2 => timings.part_2 = timing,
+                _ => {}
+            }
+        });
+
+    timings
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{day_budget, timing_from_records};
+    use std::time::Duration;
+
+    use crate::{
+        day,
+        template::{
+            protocol::{PartRecord, Status},
+            stats::Stats,
+        },
+    };
+
+    fn record(part: u8, answer: Option<&str>, nanos: u64, samples: u128) -> PartRecord {
+        PartRecord {
+            part,
+            status: if answer.is_some() {
+                Status::Solved
+            } else {
+                Status::Unsolved
+            },
+            answer: answer.map(Into::into),
+            message: None,
+            stats: Stats {
+                samples,
+                ..Stats::single(Duration::from_nanos(nanos))
+            },
+            memory_peak_bytes: None,
+        }
     }
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
-
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+    #[test]
+    fn budgets_benching_in_timed_runs() {
+        let timeout = Duration::from_secs(1);
+        assert_eq!(day_budget(timeout, false), Duration::from_secs(3));
+        assert_eq!(day_budget(timeout, true), Duration::from_secs(39));
     }
 
-    /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
-    #[cfg(feature = "test_lib")]
-    macro_rules! assert_approx_eq {
-        ($a:expr, $b:expr) => {{
-            let (a, b) = (&$a, &$b);
-            assert!(
-                (*a - *b).abs() < 1.0e-6,
-                "{} is not approximately equal to {}",
-                *a,
-                *b
-            );
-        }};
+    #[test]
+    fn builds_timing_from_records() {
+        let res = timing_from_records(
+            &[
+                record(1, Some("0"), 74, 100_000),
+                record(2, Some("10"), 74_130_000, 99_999),
+            ],
+            day!(1),
+        );
+        assert_eq!(res.total_nanos(), 74_130_074_f64);
+        assert_eq!(res.part_1.unwrap().to_string(), "74.0ns");
+        assert_eq!(res.part_1.unwrap().samples, 100_000);
+        assert_eq!(res.part_2.unwrap().to_string(), "74.1ms");
     }
 
-    #[cfg(feature = "test_lib")]
-    mod tests {
-        use super::parse_exec_time;
-
-        use crate::day;
-
-        #[test]
-        fn parses_execution_times() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
-        }
+    #[test]
+    fn builds_timing_with_parse_phase() {
+        let parse = PartRecord {
+            status: Status::Solved,
+            ..record(0, None, 1_000, 100)
+        };
+        let res = timing_from_records(
+            &[
+                parse,
+                record(1, Some("0"), 2_000, 100),
+                record(2, Some("1"), 3_000, 100),
+            ],
+            day!(1),
+        );
+        assert_eq!(res.parse.unwrap().to_string(), "1.0µs");
+        assert_eq!(res.part_1.unwrap().to_string(), "2.0µs");
+        assert_eq!(res.total_nanos(), 6_000_f64);
+    }
 
-        #[test]
-        fn parses_with_patterns_in_input() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
-        }
+    #[test]
+    fn builds_timing_with_patterns_in_answer() {
+        let line =
+            record(1, Some("@ @ @ ( ) ms (2s @ 5 samples)"), 2_000_000_000, 5).to_json_line();
+        let parsed = line.parse::<PartRecord>().unwrap();
+        let res = timing_from_records(&[parsed], day!(1));
+        assert_eq!(res.total_nanos(), 2_000_000_000_f64);
+        assert_eq!(res.part_1.unwrap().to_string(), "2.0s");
+        assert_eq!(res.part_2, None);
+    }
 
-        #[test]
-        fn parses_missing_parts() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 0_f64);
-            assert_eq!(res.part_1.is_none(), true);
-            assert_eq!(res.part_2.is_none(), true);
-        }
+    #[test]
+    fn builds_timing_with_missing_parts() {
+        let res = timing_from_records(&[record(1, None, 10, 1), record(2, None, 10, 1)], day!(1));
+        assert_eq!(res.total_nanos(), 0_f64);
+        assert_eq!(res.part_1.is_none(), true);
+        assert_eq!(res.part_2.is_none(), true);
     }
 }
@@ -1,65 +1,358 @@
 /// Encapsulates code that interacts with solution functions.
+use std::any::Any;
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, env, process, thread};
 
+use crate::template::answers::{Answers, Verdict};
+use crate::template::aoc_client::{self, AocClient};
+use crate::template::memory;
+use crate::template::protocol::{self, PartRecord, Status, PARSE_PART};
+use crate::template::stats::Stats;
+use crate::template::submission::{self, Outcome, Submissions};
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
 
+/// Command-line option that sets the part timeout in seconds.
+pub const TIMEOUT_OPTION: &str = "--timeout";
+
+/// Controls how solution parts are executed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunOptions {
+    /// Bench parts instead of executing them once.
+    pub is_timed: bool,
+    /// Do not print anything while a part runs.
+    pub is_quiet: bool,
+    /// End the process if the first execution of a part takes longer than this.
+    pub timeout: Option<Duration>,
+}
+
+impl RunOptions {
+    /// Reads the options passed to a solution binary.
+    pub fn from_args() -> Self {
+        RunOptions {
+            is_timed: env::args().any(|x| x == "--time"),
+            is_quiet: protocol::is_json_mode(),
+            timeout: part_timeout(),
+        }
+    }
+}
+
+/// Progress of a solution that runs in-process, see [`run_part_in_process`].
+#[derive(Clone, Debug)]
+pub enum Progress {
+    /// The first execution of a part (or the parse phase) started.
+    Started(u8),
+    /// The first execution of a part finished, benching starts now if requested.
+    Executed(u8),
+    Finished(PartRecord),
+}
+
+/// Signature of the function that the `solution!` macro generates for running a day in-process.
+pub type InProcessSolver = fn(&str, RunOptions, &mut dyn FnMut(Progress));
+
+/// A solution that is compiled into the main binary, so that it can run without spawning its own binary.
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub day: Day,
+    pub solve: InProcessSolver,
+}
+
 pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
+    let options = RunOptions::from_args();
+
+    let timed = run_timed(func, input, part, options, |result| {
+        if !options.is_quiet {
+            print_result(result, &part_str, None, "");
+        }
+    });
+
+    let (result, stats, memory_peak_bytes) = match timed {
+        Ok(timed) => timed,
+        Err((message, elapsed)) => {
+            // NOTE: a panicking part is reported, but does not stop the other parts from running.
+            report_failure(part, Status::Panicked, &message, elapsed);
+            return;
+        }
+    };
+
+    if protocol::is_json_mode() {
+        let record = solved_record(part, &result, stats, memory_peak_bytes);
+        println!("{}", record.to_json_line());
+        return;
+    }
+
+    let answer = result.as_ref().map(ToString::to_string);
+
+    let verdict = Answers::read_or_warn().check(day, part, answer.as_deref());
+    print_result(&result, &part_str, Some(&verdict), &format_duration(&stats));
+
+    if let Verdict::Wrong { expected } = &verdict {
+        println!("{part_str}: expected {ANSI_BOLD}{expected}{ANSI_RESET}");
+    }
+
+    let Some(answer) = answer else {
+        return;
+    };
+
+    if env::args().any(|x| x == "--accept") {
+        accept_answer(day, part, &answer);
+    }
+
+    if submit_result(&answer, day, part).is_some_and(|outcome| outcome == Outcome::Correct) {
+        accept_answer(day, part, &answer);
+    }
+}
+
+/// Records `answer` as the correct answer for a part in the answer store.
+fn accept_answer(day: Day, part: u8, answer: &str) {
+    let mut answers = match Answers::read_from_file() {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("Refusing to overwrite accepted answers: {e}");
+            return;
+        }
+    };
+    answers.accept(day, part, answer);
+    match answers.store_file() {
+        Ok(()) => println!("Accepted answer for part {part}."),
+        Err(e) => eprintln!("Failed to store accepted answer: {e}"),
+    }
+}
+
+/// Run the parse phase of a solution and return its result. Parsing is timed like a part.
+pub fn run_parse<'a, P>(func: impl Fn(&'a str) -> P, input: &'a str) -> P {
+    let options = RunOptions::from_args();
+
+    let timed = run_timed(func, input, PARSE_PART, options, |_| {
+        if !options.is_quiet {
+            print!("Parse:");
+        }
+    });
+
+    let (parsed, stats, memory_peak_bytes) = match timed {
+        Ok(timed) => timed,
+        Err((message, elapsed)) => {
+            report_failure(PARSE_PART, Status::Panicked, &message, elapsed);
+            // without a parsed input, none of the parts can run.
+            process::exit(1);
+        }
+    };
+
+    if protocol::is_json_mode() {
+        println!("{}", parse_record(stats, memory_peak_bytes).to_json_line());
+    } else {
+        print_parse(&stats);
+    }
+
+    parsed
+}
+
+/// Run a solution part without printing anything, reporting its progress to `on_progress` instead.
+pub fn run_part_in_process<I: Copy, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    part: u8,
+    options: RunOptions,
+    on_progress: &mut dyn FnMut(Progress),
+) {
+    on_progress(Progress::Started(part));
+
+    let timed = run_timed(func, input, part, options, |_| {
+        on_progress(Progress::Executed(part));
+    });
+
+    let record = match timed {
+        Ok((result, stats, memory_peak_bytes)) => {
+            solved_record(part, &result, stats, memory_peak_bytes)
+        }
+        Err((message, elapsed)) => failure_record(part, Status::Panicked, &message, elapsed),
+    };
+
+    on_progress(Progress::Finished(record));
+}
+
+/// Run the parse phase of a solution without printing anything, see [`run_part_in_process`].
+/// Returns `None` if parsing panicked.
+pub fn run_parse_in_process<'a, P>(
+    func: impl Fn(&'a str) -> P,
+    input: &'a str,
+    options: RunOptions,
+    on_progress: &mut dyn FnMut(Progress),
+) -> Option<P> {
+    on_progress(Progress::Started(PARSE_PART));
+
+    let timed = run_timed(func, input, PARSE_PART, options, |_| {
+        on_progress(Progress::Executed(PARSE_PART));
+    });
+
+    match timed {
+        Ok((parsed, stats, memory_peak_bytes)) => {
+            on_progress(Progress::Finished(parse_record(stats, memory_peak_bytes)));
+            Some(parsed)
+        }
+        Err((message, elapsed)) => {
+            let record = failure_record(PARSE_PART, Status::Panicked, &message, elapsed);
+            on_progress(Progress::Finished(record));
+            None
+        }
+    }
+}
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+fn solved_record<T: Display>(
+    part: u8,
+    result: &Option<T>,
+    stats: Stats,
+    memory_peak_bytes: Option<u64>,
+) -> PartRecord {
+    PartRecord {
+        part,
+        status: if result.is_some() {
+            Status::Solved
+        } else {
+            Status::Unsolved
+        },
+        answer: result.as_ref().map(ToString::to_string),
+        message: None,
+        stats,
+        memory_peak_bytes,
+    }
+}
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+fn parse_record(stats: Stats, memory_peak_bytes: Option<u64>) -> PartRecord {
+    PartRecord {
+        part: PARSE_PART,
+        status: Status::Solved,
+        answer: None,
+        message: None,
+        stats,
+        memory_peak_bytes,
+    }
+}
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+pub(crate) fn failure_record(
+    part: u8,
+    status: Status,
+    message: &str,
+    elapsed: Duration,
+) -> PartRecord {
+    PartRecord {
+        part,
+        status,
+        answer: None,
+        message: Some(message.into()),
+        stats: Stats::single(elapsed),
+        memory_peak_bytes: None,
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///
+/// The peak heap usage is measured during the first execution, if it is known, see [`memory`].
+///
+/// If the first execution panics, `Err` is returned with the panic message. If it exceeds the part timeout,
+/// the timeout is reported and the process exits, since there is no way to stop the running part.
 fn run_timed<I: Copy, T>(
     func: impl Fn(I) -> T,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    part: u8,
+    options: RunOptions,
+    hook: impl FnOnce(&T),
+) -> Result<(T, Stats, Option<u64>), (String, Duration)> {
+    let watchdog = options.timeout.map(|timeout| start_watchdog(part, timeout));
+
     let timer = Instant::now();
-    let result = {
-        #[cfg(feature = "dhat-heap")]
-        let _profiler = dhat::Profiler::new_heap();
+    let (result, memory_peak_bytes) = memory::measure(|| {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            #[cfg(feature = "dhat-heap")]
+            let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
-    };
+            func(input)
+        }))
+    });
     let base_time = timer.elapsed();
 
+    drop(watchdog);
+
+    let result = result.map_err(|payload| (panic_message(payload.as_ref()), base_time))?;
+
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let stats = if options.is_timed {
+        bench(func, input, &base_time, options.is_quiet)
     } else {
-        (base_time, 1)
+        Stats::single(base_time)
     };
 
-    (result, run.0, run.1)
+    Ok((result, stats, memory_peak_bytes))
+}
+
+/// Reads the part timeout passed via `--timeout <seconds>`. A value of `0` disables the timeout.
+fn part_timeout() -> Option<Duration> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == TIMEOUT_OPTION)?;
+    let secs: u64 = args.get(index + 1)?.parse().ok()?;
+    (secs > 0).then(|| Duration::from_secs(secs))
 }
 
-fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
+/// Starts a thread that ends the process if the returned sender is not dropped before the timeout.
+fn start_watchdog(part: u8, timeout: Duration) -> mpsc::Sender<()> {
+    let (sender, receiver) = mpsc::channel::<()>();
+
+    thread::spawn(move || {
+        if receiver.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+            let message = format!("exceeded {timeout:?}");
+            report_failure(part, Status::TimedOut, &message, timeout);
+            process::exit(1);
+        }
+    });
+
+    sender
+}
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".into()
+    }
+}
+
+fn report_failure(part: u8, status: Status, message: &str, elapsed: Duration) {
+    if protocol::is_json_mode() {
+        let record = failure_record(part, status, message, elapsed);
+        println!("{}", record.to_json_line());
+    } else {
+        print_failure(part, status, message);
+    }
+}
+
+/// Collects the full sample distribution of a solution part. See [`Stats`] for how it is summarized.
+fn bench<I: Copy, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+    is_quiet: bool,
+) -> Stats {
+    if !is_quiet {
+        print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout().flush();
+    }
 
     let bench_iterations =
         (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
-    let mut timers: Vec<Duration> = vec![];
+    #[allow(clippy::cast_possible_truncation)]
+    let mut timers: Vec<Duration> = Vec::with_capacity(bench_iterations as usize);
 
     for _ in 0..bench_iterations {
         let timer = Instant::now();
@@ -67,36 +360,79 @@ fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (
         timers.push(timer.elapsed());
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    Stats::from_samples(&timers).unwrap_or_else(|| Stats::single(*base_time))
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
+/// Formats the timing of a part. Benched parts are reported by their median, followed by the spread of the samples.
+fn format_duration(stats: &Stats) -> String {
+    if stats.samples == 1 {
+        format!(" ({:.1?})", stats.median)
+    } else {
+        format!(
+            " ({:.1?} @ {} samples) [min {:.1?}, mean {:.1?}, p95 {:.1?}, σ {:.1?}, {} outliers]",
+            stats.median,
+            stats.samples,
+            stats.min,
+            stats.mean,
+            stats.p95,
+            stats.std_dev,
+            stats.outliers
+        )
+    }
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
-    } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+/// Prints a record received from a solution binary the same way `run_part` would have printed it.
+pub(crate) fn print_record(record: &PartRecord, verdict: Option<&Verdict>) {
+    if matches!(record.status, Status::Panicked | Status::TimedOut) {
+        print_failure(
+            record.part,
+            record.status,
+            record.message.as_deref().unwrap_or_default(),
+        );
+        return;
     }
+
+    if record.part == PARSE_PART {
+        print_parse(&record.stats);
+        return;
+    }
+
+    print_result(
+        &record.answer,
+        &format!("Part {}", record.part),
+        verdict,
+        &format_duration(&record.stats),
+    );
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_failure(part: u8, status: Status, message: &str) {
+    let label = if part == PARSE_PART {
+        "Parse".to_string()
+    } else {
+        format!("Part {part}")
+    };
+    print!("\r");
+    println!("{label}: ✖ {status}: {message}");
+}
+
+fn print_parse(stats: &Stats) {
+    print!("\r");
+    println!("Parse:{}", format_duration(stats));
+}
+
+fn print_result<T: Display>(
+    result: &Option<T>,
+    part: &str,
+    verdict: Option<&Verdict>,
+    duration_str: &str,
+) {
     let is_intermediate_result = duration_str.is_empty();
+    let verdict_str = verdict.map(|v| format!(" {v}")).unwrap_or_default();
 
     match result {
         Some(result) => {
             if result.to_string().contains('\n') {
-                let str = format!("{part}: ▼ {duration_str}");
+                let str = format!("{part}: ▼{verdict_str} {duration_str}");
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
@@ -105,7 +441,8 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                     println!("{result}");
                 }
             } else {
-                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
+                let str =
+                    format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{verdict_str}{duration_str}");
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
@@ -127,12 +464,11 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
-    part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+///  2. aoc-cli is installed, if `AOC_CLIENT` selects it.
+///  3. the submission log does not show that the answer is wrong or that submissions are locked.
+///
+/// Returns the outcome of the submission and logs it.
+fn submit_result(result: &str, day: Day, part: u8) -> Option<Outcome> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
@@ -155,11 +491,55 @@ fn submit_result<T: Display>(
         return None;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+    let mut submissions = match Submissions::read_from_file() {
+        Ok(submissions) => submissions,
+        Err(e) => {
+            eprintln!("Not submitting {result}: {e}");
+            return None;
+        }
+    };
+    if let Err(refusal) = submissions.check(day, part, result, submission::now()) {
+        eprintln!("Not submitting {result}: {refusal}");
+        return None;
+    }
+
+    let response = if aoc_client::uses_aoc_cli() {
+        if aoc_cli::check().is_err() {
+            eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+            process::exit(1);
+        }
+
+        println!("Submitting result via aoc-cli...");
+        aoc_cli::submit(day, part, result).map_err(|e| e.to_string())
+    } else {
+        println!("Submitting result...");
+        AocClient::from_env()
+            .and_then(|client| client.submit(day, part, result))
+            .inspect(|response| print!("{}", response.message))
+            .map_err(|e| e.to_string())
+    };
+
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("failed to submit result: {e}");
+            return None;
+        }
+    };
+
+    match response.wait {
+        Some(wait) => println!(
+            "Part {part}: {} (next submission in {})",
+            response.outcome,
+            submission::format_wait(wait)
+        ),
+        None => println!("Part {part}: {}", response.outcome),
+    }
+
+    submissions.record(day, part, result, &response, submission::now());
+    if let Err(e) = submissions.store_file() {
+        eprintln!("failed to store submission: {e}");
     }
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    Some(response.outcome)
 }
@@ -0,0 +1,153 @@
+//! Summary statistics over the samples collected while benching a solution part.
+use std::time::Duration;
+
+/// Samples whose modified z-score exceeds this value are rejected as outliers.
+/// See: Iglewicz & Hoaglin, "How to Detect and Handle Outliers" (1993).
+const OUTLIER_THRESHOLD: f64 = 3.5;
+
+/// Scales the median absolute deviation to be consistent with the standard deviation of a normal distribution.
+const MAD_SCALE: f64 = 0.6745;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    /// Number of collected samples, including rejected outliers.
+    pub samples: u128,
+    /// Number of samples that were rejected as outliers.
+    pub outliers: u128,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    /// Statistics for a part that was executed exactly once.
+    pub fn single(duration: Duration) -> Self {
+        Stats {
+            samples: 1,
+            outliers: 0,
+            min: duration,
+            median: duration,
+            mean: duration,
+            p95: duration,
+            std_dev: Duration::ZERO,
+        }
+    }
+
+    /// Computes statistics over `timers`, ignoring outliers detected via the median absolute deviation.
+    /// Returns `None` if no samples were passed.
+    pub fn from_samples(timers: &[Duration]) -> Option<Self> {
+        let mut nanos: Vec<f64> = timers.iter().map(|d| d.as_nanos() as f64).collect();
+
+        if nanos.is_empty() {
+            return None;
+        }
+
+        nanos.sort_unstable_by(f64::total_cmp);
+
+        let retained = reject_outliers(&nanos);
+
+        let mean = retained.iter().sum::<f64>() / retained.len() as f64;
+
+        let variance = if retained.len() > 1 {
+            retained.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (retained.len() - 1) as f64
+        } else {
+            0.0
+        };
+
+        Some(Stats {
+            samples: nanos.len() as u128,
+            outliers: (nanos.len() - retained.len()) as u128,
+            min: to_duration(retained[0]),
+            median: to_duration(median(&retained)),
+            mean: to_duration(mean),
+            p95: to_duration(percentile(&retained, 0.95)),
+            std_dev: to_duration(variance.sqrt()),
+        })
+    }
+}
+
+/// Removes outliers from a sorted slice of samples.
+/// If the median absolute deviation is zero, every sample is kept.
+fn reject_outliers(sorted: &[f64]) -> Vec<f64> {
+    let med = median(sorted);
+
+    let mut deviations: Vec<f64> = sorted.iter().map(|x| (x - med).abs()).collect();
+    deviations.sort_unstable_by(f64::total_cmp);
+    let mad = median(&deviations);
+
+    if mad == 0.0 {
+        return sorted.to_vec();
+    }
+
+    sorted
+        .iter()
+        .copied()
+        .filter(|x| MAD_SCALE * (x - med).abs() / mad <= OUTLIER_THRESHOLD)
+        .collect()
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Nearest-rank percentile of a sorted, non-empty slice.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn to_duration(nanos: f64) -> Duration {
+    Duration::from_nanos(nanos.round() as u64)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Stats;
+    use std::time::Duration;
+
+    fn nanos(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&x| Duration::from_nanos(x)).collect()
+    }
+
+    #[test]
+    fn handles_empty_samples() {
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn computes_statistics() {
+        let stats = Stats::from_samples(&nanos(&[10, 20, 30, 40, 50])).unwrap();
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.min, Duration::from_nanos(10));
+        assert_eq!(stats.median, Duration::from_nanos(30));
+        assert_eq!(stats.mean, Duration::from_nanos(30));
+        assert_eq!(stats.p95, Duration::from_nanos(50));
+        assert_eq!(stats.std_dev, Duration::from_nanos(16));
+    }
+
+    #[test]
+    fn rejects_outliers() {
+        let stats = Stats::from_samples(&nanos(&[10, 11, 9, 10, 12, 10, 5000])).unwrap();
+        assert_eq!(stats.samples, 7);
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.median, Duration::from_nanos(10));
+        assert_eq!(stats.p95, Duration::from_nanos(12));
+    }
+
+    #[test]
+    fn keeps_samples_without_spread() {
+        let stats = Stats::from_samples(&nanos(&[10, 10, 10, 10, 50])).unwrap();
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.mean, Duration::from_nanos(18));
+    }
+}
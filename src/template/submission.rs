@@ -0,0 +1,585 @@
+//! Outcomes of submitting answers, and a log of past submissions.
+//!
+//! Every wrong answer locks submissions for a while, and submitting during a cooldown extends it. `--submit` keeps a
+//! log in `data/submissions.json` and uses it to refuse answers that cannot be right: an answer that was already
+//! wrong, one outside the bounds of answers that were too high or too low, and any answer during a cooldown.
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::Day;
+
+static SUBMISSIONS_FILE_PATH: &str = "./data/submissions.json";
+
+/// How the website judged a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint.
+    Incorrect,
+    /// The answer was submitted during a cooldown and not judged.
+    RateLimited,
+    /// The part is already solved or not unlocked yet.
+    WrongLevel,
+    Unknown,
+}
+
+impl Outcome {
+    /// Whether the answer was judged and is wrong.
+    pub fn is_wrong(self) -> bool {
+        matches!(
+            self,
+            Outcome::TooHigh | Outcome::TooLow | Outcome::Incorrect
+        )
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::TooHigh => "too_high",
+            Outcome::TooLow => "too_low",
+            Outcome::Incorrect => "incorrect",
+            Outcome::RateLimited => "rate_limited",
+            Outcome::WrongLevel => "wrong_level",
+            Outcome::Unknown => "unknown",
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "✔ correct"),
+            Outcome::TooHigh => write!(f, "✖ too high"),
+            Outcome::TooLow => write!(f, "✖ too low"),
+            Outcome::Incorrect => write!(f, "✖ incorrect"),
+            Outcome::RateLimited => write!(f, "⏳ submitted too recently"),
+            Outcome::WrongLevel => write!(f, "? already solved or locked"),
+            Outcome::Unknown => write!(f, "? unknown response"),
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Outcome::Correct,
+            Outcome::TooHigh,
+            Outcome::TooLow,
+            Outcome::Incorrect,
+            Outcome::RateLimited,
+            Outcome::WrongLevel,
+            Outcome::Unknown,
+        ]
+        .into_iter()
+        .find(|outcome| outcome.key() == s)
+        .ok_or(format!("unknown submission outcome `{s}`."))
+    }
+}
+
+/// The response to a submitted answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub outcome: Outcome,
+    /// How long to wait before the next answer can be submitted.
+    pub wait: Option<Duration>,
+    pub message: String,
+}
+
+impl Response {
+    /// Reads the outcome and cooldown from the message the website responded with.
+    pub fn parse(message: &str) -> Self {
+        let text = message.to_ascii_lowercase();
+
+        let outcome = if text.contains("that's the right answer") {
+            Outcome::Correct
+        } else if text.contains("that's not the right answer") {
+            if text.contains("too high") {
+                Outcome::TooHigh
+            } else if text.contains("too low") {
+                Outcome::TooLow
+            } else {
+                Outcome::Incorrect
+            }
+        } else if text.contains("you gave an answer too recently") {
+            Outcome::RateLimited
+        } else if text.contains("solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown
+        };
+
+        Response {
+            outcome,
+            wait: parse_wait(&text),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Reads a cooldown from "You have 4m 21s left to wait." or "please wait one minute before trying again."
+fn parse_wait(text: &str) -> Option<Duration> {
+    if let Some(end) = text.find(" left to wait") {
+        let start = text[..end].rfind("you have ")? + "you have ".len();
+        return text[start..end]
+            .split_whitespace()
+            .map(|amount| {
+                let unit = amount.chars().last()?;
+                let value = &amount[..amount.len() - unit.len_utf8()];
+                let seconds = match unit {
+                    'h' => 3600,
+                    'm' => 60,
+                    's' => 1,
+                    _ => return None,
+                };
+                value.parse::<u64>().ok().map(|value| value * seconds)
+            })
+            .sum::<Option<u64>>()
+            .map(Duration::from_secs);
+    }
+
+    let start = text.find("please wait ")? + "please wait ".len();
+    let mut words = text[start..].split_whitespace();
+    let value = match words.next()? {
+        "a" | "one" => 1,
+        value => value.parse().ok()?,
+    };
+    let unit = words.next()?;
+    let seconds = if unit.starts_with("second") {
+        1
+    } else if unit.starts_with("minute") {
+        60
+    } else if unit.starts_with("hour") {
+        3600
+    } else {
+        return None;
+    };
+    Some(Duration::from_secs(value * seconds))
+}
+
+/// A submitted answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    pub day: Day,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    /// Until when submissions for the day are locked, in seconds since the unix epoch.
+    pub locked_until: Option<u64>,
+}
+
+/// Why an answer is not submitted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Refusal {
+    Solved { answer: String },
+    KnownWrong { outcome: Outcome },
+    NotBelow { too_high: String },
+    NotAbove { too_low: String },
+    Cooldown { remaining: Duration },
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Refusal::Solved { answer } => write!(f, "the part was already solved with {answer}."),
+            Refusal::KnownWrong { outcome } => {
+                write!(f, "the answer was already submitted ({outcome}).")
+            }
+            Refusal::NotBelow { too_high } => {
+                write!(
+                    f,
+                    "the answer is not lower than {too_high}, which was too high."
+                )
+            }
+            Refusal::NotAbove { too_low } => {
+                write!(
+                    f,
+                    "the answer is not higher than {too_low}, which was too low."
+                )
+            }
+            Refusal::Cooldown { remaining } => write!(
+                f,
+                "submissions are locked for another {}.",
+                format_wait(*remaining)
+            ),
+        }
+    }
+}
+
+/// Represents all submitted answers.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Submissions {
+    pub data: Vec<Attempt>,
+}
+
+impl Submissions {
+    /// Dehydrate submissions to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(SUBMISSIONS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate submissions from a JSON file. If not present, returns no submissions.
+    /// Fails if the file cannot be read or parsed, since submitting without the log could repeat a wrong answer.
+    pub fn read_from_file() -> Result<Self, String> {
+        match fs::read_to_string(SUBMISSIONS_FILE_PATH) {
+            Ok(contents) => Submissions::try_from(contents)
+                .map_err(|e| format!("\"{SUBMISSIONS_FILE_PATH}\" is corrupt, {e}")),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Submissions::default()),
+            Err(e) => Err(format!("could not read \"{SUBMISSIONS_FILE_PATH}\": {e}")),
+        }
+    }
+
+    /// Logs the response to submitting `answer` at `now` (seconds since the unix epoch).
+    pub fn record(&mut self, day: Day, part: u8, answer: &str, response: &Response, now: u64) {
+        self.data.push(Attempt {
+            day,
+            part,
+            answer: answer.into(),
+            outcome: response.outcome,
+            timestamp: now,
+            locked_until: response.wait.map(|wait| now + wait.as_secs()),
+        });
+    }
+
+    /// Checks whether `answer` may be submitted at `now` (seconds since the unix epoch).
+    pub fn check(&self, day: Day, part: u8, answer: &str, now: u64) -> Result<(), Refusal> {
+        let locked_until = self
+            .data
+            .iter()
+            .filter(|attempt| attempt.day == day)
+            .filter_map(|attempt| attempt.locked_until)
+            .max();
+        if let Some(locked_until) = locked_until.filter(|&until| until > now) {
+            return Err(Refusal::Cooldown {
+                remaining: Duration::from_secs(locked_until - now),
+            });
+        }
+
+        let attempts = self
+            .data
+            .iter()
+            .filter(|attempt| attempt.day == day && attempt.part == part);
+        let value = answer.trim().parse::<i128>().ok();
+
+        for attempt in attempts {
+            if attempt.outcome == Outcome::Correct {
+                return Err(Refusal::Solved {
+                    answer: attempt.answer.clone(),
+                });
+            }
+            if attempt.outcome.is_wrong() && attempt.answer == answer {
+                return Err(Refusal::KnownWrong {
+                    outcome: attempt.outcome,
+                });
+            }
+
+            let bound = attempt.answer.trim().parse::<i128>().ok();
+            match (attempt.outcome, value, bound) {
+                (Outcome::TooHigh, Some(value), Some(bound)) if value >= bound => {
+                    return Err(Refusal::NotBelow {
+                        too_high: attempt.answer.clone(),
+                    });
+                }
+                (Outcome::TooLow, Some(value), Some(bound)) if value <= bound => {
+                    return Err(Refusal::NotAbove {
+                        too_low: attempt.answer.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Seconds since the unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Formats a cooldown like the website does, e.g. `4m 21s`.
+pub fn format_wait(wait: Duration) -> String {
+    let seconds = wait.as_secs();
+    match (seconds / 60, seconds % 60) {
+        (0, seconds) => format!("{seconds}s"),
+        (minutes, seconds) => format!("{minutes}m {seconds}s"),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Submissions> for JsonValue {
+    fn from(value: Submissions) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Submissions {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Submissions {
+            data: json_data
+                .iter()
+                .map(Attempt::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Attempt> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Attempt) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("answer".into(), JsonValue::String(value.answer.clone()));
+        map.insert(
+            "outcome".into(),
+            JsonValue::String(value.outcome.key().into()),
+        );
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert(
+            "locked_until".into(),
+            value
+                .locked_until
+                .map_or(JsonValue::Null, |until| JsonValue::Number(until as f64)),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Attempt {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected submission to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected submission.day to be a Day struct.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .filter(|&&part| part == 1.0 || part == 2.0)
+            .ok_or("Expected submission.part to be 1 or 2.")?;
+
+        let answer = json
+            .get("answer")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected submission.answer to be a string.")?;
+
+        let outcome = json
+            .get("outcome")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected submission.outcome to be a string.")?
+            .parse()?;
+
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>())
+            .ok_or("Expected submission.timestamp to be a number.")?;
+
+        let locked_until = match json.get("locked_until") {
+            None => None,
+            Some(v) if v.is_null() => None,
+            Some(v) => Some(
+                *v.get::<f64>()
+                    .ok_or("Expected submission.locked_until to be null or a number.")?
+                    as u64,
+            ),
+        };
+
+        Ok(Attempt {
+            day,
+            part: if *part == 1.0 { 1 } else { 2 },
+            answer: answer.clone(),
+            outcome,
+            timestamp: *timestamp as u64,
+            locked_until,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Outcome, Refusal, Response, Submissions};
+    use crate::day;
+
+    #[test]
+    fn parses_responses() {
+        let parse = |message| {
+            let response = Response::parse(message);
+            (response.outcome, response.wait.map(|wait| wait.as_secs()))
+        };
+
+        assert_eq!(
+            parse("That's the right answer! You are *one gold star* closer to finding the Chief Historian. [[Continue to Part Two]](/2024/day/6#part2)"),
+            (Outcome::Correct, None)
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too high. If you're stuck, make sure you're using the full input data. Please wait one minute before trying again. [[Return to Day 6]](/2024/day/6)"),
+            (Outcome::TooHigh, Some(60))
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too low. Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again."),
+            (Outcome::TooLow, Some(300))
+        );
+        assert_eq!(
+            parse("That's not the right answer. If you're stuck, ... please wait one minute before trying again."),
+            (Outcome::Incorrect, Some(60))
+        );
+        assert_eq!(
+            parse("You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 4m 21s left to wait."),
+            (Outcome::RateLimited, Some(261))
+        );
+        assert_eq!(
+            parse("You don't seem to be solving the right level. Did you already complete it?"),
+            (Outcome::WrongLevel, None)
+        );
+        assert_eq!(parse("Internal Server Error"), (Outcome::Unknown, None));
+    }
+
+    #[test]
+    fn refuses_impossible_answers() {
+        let mut submissions = Submissions::default();
+        let now = 1_733_461_200;
+        submissions.record(
+            day!(6),
+            1,
+            "5000",
+            &Response::parse("your answer is too high. That's not the right answer"),
+            now,
+        );
+        submissions.record(
+            day!(6),
+            1,
+            "4000",
+            &Response::parse("That's not the right answer; your answer is too low. Please wait one minute before trying again."),
+            now + 30,
+        );
+
+        assert_eq!(
+            submissions.check(day!(6), 1, "4500", now + 60),
+            Err(Refusal::Cooldown {
+                remaining: Duration::from_secs(30)
+            })
+        );
+
+        let later = now + 120;
+        assert_eq!(submissions.check(day!(6), 1, "4500", later), Ok(()));
+        assert_eq!(
+            submissions.check(day!(6), 1, "4000", later),
+            Err(Refusal::KnownWrong {
+                outcome: Outcome::TooLow
+            })
+        );
+        assert_eq!(
+            submissions.check(day!(6), 1, "5001", later),
+            Err(Refusal::NotBelow {
+                too_high: "5000".into()
+            })
+        );
+        assert_eq!(
+            submissions.check(day!(6), 1, "3999", later),
+            Err(Refusal::NotAbove {
+                too_low: "4000".into()
+            })
+        );
+        // bounds do not apply to answers that are not numbers, or to other parts.
+        assert_eq!(submissions.check(day!(6), 1, "ABC", later), Ok(()));
+        assert_eq!(submissions.check(day!(6), 2, "5001", later), Ok(()));
+
+        submissions.record(
+            day!(6),
+            1,
+            "4559",
+            &Response::parse("That's the right answer!"),
+            later,
+        );
+        assert_eq!(
+            submissions.check(day!(6), 1, "4500", later),
+            Err(Refusal::Solved {
+                answer: "4559".into()
+            })
+        );
+    }
+
+    #[test]
+    fn roundtrips_json() {
+        let mut submissions = Submissions::default();
+        submissions.record(
+            day!(6),
+            2,
+            "1382",
+            &Response::parse("You have 31s left to wait."),
+            1_733_461_200,
+        );
+        submissions.record(
+            day!(6),
+            2,
+            "1383",
+            &Response::parse("That's the right answer!"),
+            1_733_461_300,
+        );
+
+        let json = tinyjson::JsonValue::from(submissions.clone())
+            .stringify()
+            .unwrap();
+        let parsed = Submissions::try_from(json).unwrap();
+        assert_eq!(parsed.data, submissions.data);
+        assert_eq!(parsed.data[0].locked_until, Some(1_733_461_231));
+    }
+}
@@ -1,17 +1,111 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    io::{Error, ErrorKind},
+    str::FromStr,
+    time::Duration,
+};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
+use crate::template::{
+    protocol::{PartRecord, PARSE_PART},
+    Day,
+};
 
 static TIMINGS_FILE_PATH: &str = "./data/timings.json";
 
+/// Version of the JSON schema of `data/timings.json`.
+/// Version 1 stored formatted medians such as `"74.1ms"` and a `total_nanos` per day, it is migrated when read.
+const SCHEMA_VERSION: u32 = 2;
+
+/// Benchmark statistics of a part, or of the parse phase.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PartTiming {
+    pub median_nanos: f64,
+    pub std_dev_nanos: f64,
+    /// Number of samples that the statistics are based on, not counting outliers.
+    /// Timings migrated from schema version 1 have no samples and no deviation.
+    pub samples: u128,
+    /// Peak heap usage of the first execution in bytes, only known for days that were run in-process.
+    pub memory_peak_bytes: Option<u64>,
+}
+
+impl PartTiming {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn median(&self) -> Duration {
+        Duration::from_nanos(self.median_nanos.round() as u64)
+    }
+}
+
+impl From<&PartRecord> for PartTiming {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &PartRecord) -> Self {
+        PartTiming {
+            median_nanos: value.stats.median.as_nanos() as f64,
+            std_dev_nanos: value.stats.std_dev.as_nanos() as f64,
+            samples: value.stats.samples - value.stats.outliers,
+            memory_peak_bytes: value.memory_peak_bytes,
+        }
+    }
+}
+
+/// Formats the median like the runner prints it, e.g. `74.1ms`.
+impl Display for PartTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1?}", self.median())
+    }
+}
+
 /// Represents benchmark times for a single day.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Timing {
     pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
-    pub total_nanos: f64,
+    pub parse: Option<PartTiming>,
+    pub part_1: Option<PartTiming>,
+    pub part_2: Option<PartTiming>,
+    /// Duration of the calibration loop on the machine that measured this day, see `calibration`.
+    pub calibration_nanos: Option<f64>,
+}
+
+impl Timing {
+    /// Returns the timing of a part, or of the parse phase for [`PARSE_PART`].
+    pub fn part(&self, part: u8) -> Option<&PartTiming> {
+        match part {
+            PARSE_PART => self.parse.as_ref(),
+            1 => self.part_1.as_ref(),
+            2 => self.part_2.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the median duration of a part (or the parse phase for [`PARSE_PART`]) in nanoseconds.
+    pub fn part_nanos(&self, part: u8) -> Option<f64> {
+        self.part(part).map(|timing| timing.median_nanos)
+    }
+
+    /// Sum of the medians of the parse phase and both parts.
+    pub fn total_nanos(&self) -> f64 {
+        [&self.parse, &self.part_1, &self.part_2]
+            .into_iter()
+            .flatten()
+            .map(|timing| timing.median_nanos)
+            .sum()
+    }
+}
+
+/// Parses a duration as printed by `{:.1?}`, e.g. `74.1ms`, to nanoseconds.
+fn parse_duration_nanos(s: &str) -> Option<f64> {
+    let unit_start = s.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (value, unit) = s.split_at(unit_start);
+    let factor = match unit {
+        "ns" => 1.0,
+        "µs" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        _ => return None,
+    };
+    value.parse::<f64>().ok().map(|value| value * factor)
 }
 
 /// Represents benchmark times for a set of days.
@@ -30,11 +124,14 @@ impl Timings {
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
-    pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
-            .map_err(|x| x.to_string())
-            .and_then(Timings::try_from)
-            .unwrap_or_default()
+    /// Fails if the file cannot be read, parsed or has an unsupported version, so that it is not overwritten.
+    pub fn read_from_file() -> Result<Self, String> {
+        match fs::read_to_string(TIMINGS_FILE_PATH) {
+            Ok(contents) => Timings::try_from(contents)
+                .map_err(|e| format!("\"{TIMINGS_FILE_PATH}\" is corrupt, {e}")),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Timings::default()),
+            Err(e) => Err(format!("could not read \"{TIMINGS_FILE_PATH}\": {e}")),
+        }
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
@@ -51,13 +148,13 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|t| t.day);
         Timings { data }
     }
 
     /// Sum up total duration of timings as millis.
     pub fn total_millis(&self) -> f64 {
-        self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
+        self.data.iter().map(Timing::total_nanos).sum::<f64>() / 1_000_000_f64
     }
 
     pub fn is_day_complete(&self, day: Day) -> bool {
@@ -73,6 +170,10 @@ impl From<Timings> for JsonValue {
     fn from(value: Timings) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
+        map.insert(
+            "version".into(),
+            JsonValue::Number(f64::from(SCHEMA_VERSION)),
+        );
         map.insert(
             "data".into(),
             JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
@@ -88,50 +189,132 @@ impl TryFrom<String> for Timings {
     fn try_from(value: String) -> Result<Self, Self::Error> {
         let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
 
-        let json_data = json
+        let json = json
             .get::<HashMap<String, JsonValue>>()
-            .ok_or("expected JSON document to be an object.")?
+            .ok_or("expected JSON document to be an object.")?;
+
+        // NOTE: files without a version were written before the schema was versioned.
+        let version = match json.get("version") {
+            Some(value) => *value
+                .get::<f64>()
+                .ok_or("expected `json.version` to be a number.")?,
+            None => 1.0,
+        };
+
+        if version != 1.0 && version != f64::from(SCHEMA_VERSION) {
+            return Err(format!("unsupported version {version} of timings file."));
+        }
+
+        let json_data = json
             .get("data")
             .ok_or("expected JSON document to have key `data`.")?
             .get::<Vec<JsonValue>>()
             .ok_or("expected `json.data` to be an array.")?;
 
-        Ok(Timings {
-            data: json_data
-                .iter()
-                .map(Timing::try_from)
-                .collect::<Result<_, _>>()?,
-        })
+        // NOTE: calibrations used to be stored once for all days, they apply to days without their own.
+        let calibration_nanos = match json.get("calibration_nanos") {
+            Some(value) => Some(
+                *value
+                    .get::<f64>()
+                    .ok_or("expected `json.calibration_nanos` to be a number.")?,
+            ),
+            None => None,
+        };
+
+        let mut data: Vec<Timing> = json_data
+            .iter()
+            .map(|timing| {
+                if version == 1.0 {
+                    Timing::try_from_v1(timing)
+                } else {
+                    Timing::try_from(timing)
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        for timing in &mut data {
+            timing.calibration_nanos = timing.calibration_nanos.or(calibration_nanos);
+        }
+
+        Ok(Timings { data })
     }
 }
 
 /* -------------------------------------------------------------------------- */
 
+impl From<&PartTiming> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &PartTiming) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("median_nanos".into(), JsonValue::Number(value.median_nanos));
+        map.insert(
+            "std_dev_nanos".into(),
+            JsonValue::Number(value.std_dev_nanos),
+        );
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+
+        if let Some(bytes) = value.memory_peak_bytes {
+            map.insert("memory_peak_bytes".into(), JsonValue::Number(bytes as f64));
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartTiming {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected part timing to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected part timing.{key} to be a number."))
+        };
+
+        // NOTE: `memory_peak_bytes` is optional, it is only known for days that were run in-process.
+        let memory_peak_bytes = match json.get("memory_peak_bytes") {
+            Some(_) => Some(number("memory_peak_bytes")? as u64),
+            None => None,
+        };
+
+        Ok(PartTiming {
+            median_nanos: number("median_nanos")?,
+            std_dev_nanos: number("std_dev_nanos")?,
+            samples: number("samples")? as u128,
+            memory_peak_bytes,
+        })
+    }
+}
+
 impl From<&Timing> for JsonValue {
     fn from(value: &Timing) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
-        map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
-
-        let part_1 = value.part_1.clone().map(JsonValue::String);
-        let part_2 = value.part_2.clone().map(JsonValue::String);
 
-        map.insert(
-            "part_1".into(),
-            match part_1 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
-        );
+        for (key, timing) in [
+            ("parse", &value.parse),
+            ("part_1", &value.part_1),
+            ("part_2", &value.part_2),
+        ] {
+            map.insert(
+                key.into(),
+                timing.as_ref().map_or(JsonValue::Null, JsonValue::from),
+            );
+        }
 
-        map.insert(
-            "part_2".into(),
-            match part_2 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
-        );
+        if let Some(calibration_nanos) = value.calibration_nanos {
+            map.insert(
+                "calibration_nanos".into(),
+                JsonValue::Number(calibration_nanos),
+            );
+        }
 
         JsonValue::Object(map)
     }
@@ -141,6 +324,34 @@ impl TryFrom<&JsonValue> for Timing {
     type Error = String;
 
     fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        Timing::try_from_version(value, |key, value| {
+            PartTiming::try_from(value).map_err(|e| format!("{e} (in timing.{key})"))
+        })
+    }
+}
+
+impl Timing {
+    /// Reads a timing of schema version 1, whose parts are formatted medians such as `"74.1ms"`.
+    fn try_from_v1(value: &JsonValue) -> Result<Self, String> {
+        Timing::try_from_version(value, |key, value| {
+            value
+                .get::<String>()
+                .and_then(|s| parse_duration_nanos(s))
+                .map(|median_nanos| PartTiming {
+                    median_nanos,
+                    std_dev_nanos: 0.0,
+                    samples: 0,
+                    memory_peak_bytes: None,
+                })
+                .ok_or(format!("Expected timing.{key} to be null or a duration."))
+        })
+    }
+
+    /// Reads the day and the parts of a timing, using `parse_part` for parts that are not null.
+    fn try_from_version(
+        value: &JsonValue,
+        parse_part: impl Fn(&str, &JsonValue) -> Result<PartTiming, String>,
+    ) -> Result<Self, String> {
         let json = value
             .get::<HashMap<String, JsonValue>>()
             .ok_or("Expected timing to be a JSON object.")?;
@@ -151,26 +362,30 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|day| Day::from_str(day).ok())
             .ok_or("Expected timing.day to be a Day struct.")?;
 
-        let part_1 = json
-            .get("part_1")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_1 to be null or string.")?;
-
-        let part_2 = json
-            .get("part_2")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_2 to be null or string.")?;
+        let part = |key: &str, is_required: bool| match json.get(key) {
+            Some(v) if v.is_null() => Ok(None),
+            Some(v) => parse_part(key, v).map(Some),
+            None if is_required => Err(format!("Expected timing.{key} to be present.")),
+            None => Ok(None),
+        };
 
-        let total_nanos = json
-            .get("total_nanos")
-            .and_then(|v| v.get::<f64>().copied())
-            .ok_or("Expected timing.total_nanos to be a number.")?;
+        // NOTE: `calibration_nanos` is optional, it is only stored since regression checks were introduced.
+        let calibration_nanos = match json.get("calibration_nanos") {
+            Some(value) => Some(
+                *value
+                    .get::<f64>()
+                    .ok_or("Expected timing.calibration_nanos to be a number.")?,
+            ),
+            None => None,
+        };
 
+        // NOTE: `parse` is optional, timings stored before parse phases were timed do not contain it.
         Ok(Timing {
             day,
-            part_1: part_1.cloned(),
-            part_2: part_2.cloned(),
-            total_nanos,
+            parse: part("parse", false)?,
+            part_1: part("part_1", true)?,
+            part_2: part("part_2", true)?,
+            calibration_nanos,
         })
     }
 }
@@ -181,46 +396,114 @@ impl TryFrom<&JsonValue> for Timing {
 mod tests {
     use crate::day;
 
-    use super::{Timing, Timings};
+    use super::{PartTiming, Timing, Timings};
+
+    fn part(median_nanos: f64) -> Option<PartTiming> {
+        Some(PartTiming {
+            median_nanos,
+            std_dev_nanos: median_nanos / 10.0,
+            samples: 100,
+            memory_peak_bytes: Some(1024),
+        })
+    }
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
-                    total_nanos: 3e+10,
+                    parse: None,
+                    part_1: part(10e6),
+                    part_2: part(20e6),
+                    calibration_nanos: None,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
-                    total_nanos: 7e+10,
+                    parse: None,
+                    part_1: part(30e6),
+                    part_2: part(40e6),
+                    calibration_nanos: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
+                    parse: None,
+                    part_1: part(40e6),
                     part_2: None,
-                    total_nanos: 4e+10,
+                    calibration_nanos: None,
                 },
             ],
         }
     }
 
     mod deserialization {
+        use super::get_mock_timings;
         use crate::{day, template::timings::Timings};
+        use tinyjson::JsonValue;
 
         #[test]
         fn handles_json_timings() {
-            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let json = r#"{ "version": 2, "data": [{ "day": "01", "parse": null, "part_1": { "median_nanos": 1000000, "std_dev_nanos": 2500, "samples": 900 }, "part_2": null }] }"#.to_string();
             let timings = Timings::try_from(json).unwrap();
             assert_eq!(timings.data.len(), 1);
             let timing = timings.data.first().unwrap();
             assert_eq!(timing.day, day!(1));
-            assert_eq!(timing.part_1, Some("1ms".to_string()));
+            assert_eq!(timing.parse, None);
+            let part_1 = timing.part_1.unwrap();
+            assert_eq!(part_1.median_nanos, 1_000_000_f64);
+            assert_eq!(part_1.std_dev_nanos, 2_500_f64);
+            assert_eq!(part_1.samples, 900);
+            assert_eq!(part_1.to_string(), "1.0ms");
+            assert_eq!(timing.part_2, None);
+            assert_eq!(timing.total_nanos(), 1_000_000_f64);
+        }
+
+        #[test]
+        fn migrates_version_1_timings() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.parse, None);
+            assert_eq!(timing.part_1.unwrap().median_nanos, 1_000_000_f64);
+            assert_eq!(timing.part_1.unwrap().samples, 0);
             assert_eq!(timing.part_2, None);
-            assert_eq!(timing.total_nanos, 1_000_000_000_f64);
+            // the total is computed from the parts.
+            assert_eq!(timing.total_nanos(), 1_000_000_f64);
+        }
+
+        #[test]
+        fn migrates_version_1_timings_with_parse() {
+            let json = r#"{ "data": [{ "day": "05", "parse": "2ms", "part_1": "1.5µs", "part_2": "74.1ms", "total_nanos": 6000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.parse.unwrap().median_nanos, 2_000_000_f64);
+            assert_eq!(timing.part_1.unwrap().median_nanos, 1_500_f64);
+            assert_eq!(timing.part_2.unwrap().to_string(), "74.1ms");
+        }
+
+        #[test]
+        fn round_trips_timings() {
+            let mut timings = get_mock_timings();
+            timings.data[1].calibration_nanos = Some(5e6);
+            let json = JsonValue::from(timings.clone()).stringify().unwrap();
+            let parsed = Timings::try_from(json).unwrap();
+            assert_eq!(parsed.data, timings.data);
+        }
+
+        #[test]
+        fn applies_shared_calibrations_to_days_without_their_own() {
+            let json = r#"{ "version": 2, "calibration_nanos": 4000000, "data": [{ "day": "01", "part_1": null, "part_2": null }, { "day": "02", "part_1": null, "part_2": null, "calibration_nanos": 5000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.data[0].calibration_nanos, Some(4e6));
+            assert_eq!(timings.data[1].calibration_nanos, Some(5e6));
+        }
+
+        #[test]
+        fn rejects_unknown_versions() {
+            let json = r#"{ "version": 3, "data": [] }"#.to_string();
+            assert!(Timings::try_from(json).is_err());
+            let json =
+                r#"{ "data": [{ "day": "01", "part_1": "fast", "part_2": null }] }"#.to_string();
+            assert!(Timings::try_from(json).is_err());
         }
 
         #[test]
@@ -266,9 +549,15 @@ mod tests {
                 3
             );
         }
+
+        #[test]
+        fn computes_totals() {
+            assert_eq!(get_mock_timings().total_millis(), 140.0);
+        }
     }
 
     mod is_day_complete {
+        use super::part;
         use crate::{
             day,
             template::timings::{Timing, Timings},
@@ -279,13 +568,14 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: Some("2ms".into()),
-                    total_nanos: 3_000_000_000_f64,
+                    parse: None,
+                    part_1: part(1e6),
+                    part_2: part(2e6),
+                    calibration_nanos: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -293,13 +583,14 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
+                    parse: None,
+                    part_1: part(1e6),
                     part_2: None,
-                    total_nanos: 1_000_000_000_f64,
+                    calibration_nanos: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -307,13 +598,14 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
+                    parse: None,
                     part_1: None,
                     part_2: None,
-                    total_nanos: 0.0,
+                    calibration_nanos: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -331,9 +623,10 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(3),
+                    parse: None,
                     part_1: None,
                     part_2: None,
-                    total_nanos: 0_f64,
+                    calibration_nanos: None,
                 }],
             };
             let merged = timings.merge(&other);
@@ -351,9 +644,10 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(2),
+                    parse: None,
                     part_1: None,
                     part_2: None,
-                    total_nanos: 0_f64,
+                    calibration_nanos: None,
                 }],
             };
             let merged = timings.merge(&other);
@@ -361,7 +655,7 @@ mod tests {
             assert_eq!(merged.data.len(), 3);
             assert_eq!(merged.data[0].day, day!(1));
             assert_eq!(merged.data[1].day, day!(2));
-            assert_eq!(merged.data[1].total_nanos, 0_f64);
+            assert_eq!(merged.data[1].total_nanos(), 0_f64);
             assert_eq!(merged.data[2].day, day!(4));
         }
 
@@ -0,0 +1,305 @@
+//! Renders grids with overlays to the terminal, to see what a simulation is doing.
+//!
+//! Build a [`Frame`] from a [`Grid`], draw paths, highlighted cells and markers on top of it and play it back
+//! with [`show`]. Solutions emit frames inside the [`visualize!`](crate::visualize!) hook, which compiles away
+//! unless the solution is built with the `visualize` feature by `cargo solve <day> --visualize` (or `--render`,
+//! see [`render`](crate::render)).
+use std::{
+    cmp::Ordering,
+    env,
+    fmt::Display,
+    io::{stderr, Write},
+    sync::OnceLock,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    geom::{Direction, Point},
+    grid::Grid,
+};
+
+/// Command-line option that enables drawing frames to the terminal.
+pub const VISUALIZE_OPTION: &str = "--visualize";
+
+/// Command-line option that enables writing images, see `render`.
+pub const RENDER_OPTION: &str = "--render";
+
+/// Command-line option that sets the delay between frames in milliseconds.
+pub const DELAY_OPTION: &str = "--delay";
+
+/// Delay between frames, unless overridden with `--delay`.
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+const ANSI_CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// Path characters by the directions they connect, see `Pixel::links`.
+const LINES: [char; 16] = [
+    ' ', '│', '─', '└', '│', '│', '┌', '├', '─', '┘', '─', '┴', '┐', '┤', '┬', '┼',
+];
+
+/// Runs the given statements only if the solution is built with the `visualize` feature.
+/// Otherwise they are compiled away, so timings are not affected.
+///
+/// ```ignore
+/// advent_of_code::visualize! {
+///     let frame = Frame::new(&grid, |&c| c).marker(guard, '^', Color::Yellow);
+///     advent_of_code::visualize::show(&frame);
+/// }
+/// ```
+#[macro_export]
+macro_rules! visualize {
+    ($($body:tt)*) => {
+        #[cfg(feature = "visualize")]
+        {
+            $($body)*
+        }
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    fn foreground(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+            Color::Gray => 90,
+        }
+    }
+
+    fn background(self) -> u8 {
+        self.foreground() + 10
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Pixel {
+    symbol: char,
+    color: Option<Color>,
+    background: Option<Color>,
+    /// Directions in which a path leaves the cell, one bit per orthogonal direction clockwise from `Up`.
+    links: u8,
+}
+
+/// A grid of colored characters with an optional caption below it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pixels: Grid<Pixel>,
+    caption: Option<String>,
+}
+
+impl Frame {
+    /// Draws every cell of `grid` as the character returned by `f`.
+    pub fn new<T>(grid: &Grid<T>, mut f: impl FnMut(&T) -> char) -> Self {
+        Frame {
+            pixels: grid.map(|cell| Pixel {
+                symbol: f(cell),
+                color: None,
+                background: None,
+                links: 0,
+            }),
+            caption: None,
+        }
+    }
+
+    /// Draws a path through `points` with box-drawing characters. Consecutive points on the same row or column
+    /// are connected, so it is enough to pass the corners of a path. Points outside of the frame are skipped.
+    pub fn path<P: Into<Point>>(
+        mut self,
+        points: impl IntoIterator<Item = P>,
+        color: Color,
+    ) -> Self {
+        let mut previous: Option<Point> = None;
+
+        for point in points.into_iter().map(Into::into) {
+            match previous {
+                Some(from) if from.x == point.x || from.y == point.y => {
+                    let mut cell = from;
+                    while cell != point {
+                        let direction = match (point.x.cmp(&cell.x), point.y.cmp(&cell.y)) {
+                            (Ordering::Greater, _) => Direction::Right,
+                            (Ordering::Less, _) => Direction::Left,
+                            (_, Ordering::Greater) => Direction::Down,
+                            _ => Direction::Up,
+                        };
+                        let next = cell
+                            .checked_add_signed(direction)
+                            .expect("the path stays between two points");
+                        self.link(cell, direction, color);
+                        self.link(next, direction.opposite(), color);
+                        cell = next;
+                    }
+                }
+                _ => self.set(point, |pixel| {
+                    pixel.symbol = '•';
+                    pixel.color = Some(color);
+                }),
+            }
+            previous = Some(point);
+        }
+        self
+    }
+
+    /// Connects the cell at `point` towards `direction` and redraws it.
+    fn link(&mut self, point: Point, direction: Direction, color: Color) {
+        self.set(point, |pixel| {
+            pixel.links |= 1 << (direction as u8 / 2);
+            pixel.symbol = LINES[pixel.links as usize];
+            pixel.color = Some(color);
+        });
+    }
+
+    /// Colors the background of the cells at `points`.
+    pub fn highlight<P: Into<Point>>(
+        mut self,
+        points: impl IntoIterator<Item = P>,
+        color: Color,
+    ) -> Self {
+        for point in points {
+            self.set(point.into(), |pixel| pixel.background = Some(color));
+        }
+        self
+    }
+
+    /// Replaces the cell at `point` with `symbol`.
+    pub fn marker(mut self, point: impl Into<Point>, symbol: char, color: Color) -> Self {
+        self.set(point.into(), |pixel| {
+            pixel.symbol = symbol;
+            pixel.color = Some(color);
+        });
+        self
+    }
+
+    pub fn caption(mut self, caption: impl Display) -> Self {
+        self.caption = Some(caption.to_string());
+        self
+    }
+
+    fn set(&mut self, point: Point, f: impl FnOnce(&mut Pixel)) {
+        if let Some(pixel) = self.pixels.get_mut(point) {
+            f(pixel);
+        }
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.pixels.rows() {
+            for pixel in row {
+                match (pixel.color, pixel.background) {
+                    (None, None) => write!(f, "{}", pixel.symbol)?,
+                    (color, background) => {
+                        let codes: Vec<String> = color
+                            .map(Color::foreground)
+                            .into_iter()
+                            .chain(background.map(Color::background))
+                            .map(|code| code.to_string())
+                            .collect();
+                        write!(f, "\x1b[{}m{}\x1b[0m", codes.join(";"), pixel.symbol)?;
+                    }
+                }
+            }
+            writeln!(f)?;
+        }
+
+        if let Some(caption) = &self.caption {
+            writeln!(f, "{caption}")?;
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Clears the terminal, draws `frame` and waits for the delay between frames, if the solution was run with
+/// `--visualize`. Frames are written to stderr, so they do not mix with the answers.
+pub fn show(frame: &Frame) {
+    if !is_enabled() {
+        return;
+    }
+
+    let mut stderr = stderr().lock();
+    // NOTE: a closed terminal should not end the solution, so write errors are ignored.
+    let _ = write!(stderr, "{ANSI_CLEAR_SCREEN}{frame}");
+    let _ = stderr.flush();
+    thread::sleep(delay());
+}
+
+/// Shows all `frames` one after another.
+pub fn play<'a>(frames: impl IntoIterator<Item = &'a Frame>) {
+    frames.into_iter().for_each(show);
+}
+
+/// Whether the solution was run with `--visualize`.
+pub fn is_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| env::args().any(|x| x == VISUALIZE_OPTION))
+}
+
+/// Reads the delay between frames from `--delay <milliseconds>` once.
+pub(crate) fn delay() -> Duration {
+    static DELAY: OnceLock<Duration> = OnceLock::new();
+    *DELAY.get_or_init(|| {
+        let args: Vec<String> = env::args().collect();
+        args.iter()
+            .position(|x| x == DELAY_OPTION)
+            .and_then(|index| args.get(index + 1)?.parse().ok())
+            .map_or(DEFAULT_DELAY, Duration::from_millis)
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Color, Frame};
+    use crate::grid::Grid;
+
+    fn plain(frame: &Frame) -> String {
+        let mut plain = String::new();
+        let mut chars = frame.to_string().chars().collect::<Vec<_>>().into_iter();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                plain.push(c);
+            }
+        }
+        plain
+    }
+
+    #[test]
+    fn draws_paths() {
+        let grid = Grid::new(4, 3, '.');
+        let frame = Frame::new(&grid, |&c| c)
+            .path([(0, 0), (3, 0), (3, 2), (1, 2), (1, 0)], Color::Blue)
+            .marker((0, 2), '^', Color::Yellow);
+        assert_eq!(plain(&frame), "─┬─┐\n.│.│\n^└─┘\n");
+    }
+
+    #[test]
+    fn colors_cells() {
+        let grid = Grid::new(2, 1, '.');
+        let frame = Frame::new(&grid, |&c| c)
+            .highlight([(1, 0), (5, 5)], Color::Red)
+            .marker((1, 0), 'O', Color::White)
+            .caption("obstructions: 1");
+        assert_eq!(frame.to_string(), ".\x1b[37;41mO\x1b[0m\nobstructions: 1\n");
+    }
+}